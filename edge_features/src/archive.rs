@@ -0,0 +1,162 @@
+use crate::compare_versions;
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+
+/// Written into a version directory once every output of the run that processed it is in place.
+pub const COMPLETE_MARKER: &str = ".complete";
+
+// What a version directory written before the marker existed has once its run got past the diff
+const LEGACY_COMPLETE_FILES: [&str; 3] = ["original.txt", "added.txt", "removed.txt"];
+
+/// The on-disk history of processed builds, laid out as `<root>/<major>/<full version>/`.
+pub struct Archive {
+    pub root: PathBuf,
+}
+
+impl Archive {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn version_dir(&self, full_version: &str) -> PathBuf {
+        let major_version: &str = full_version.split('.').next().unwrap_or(full_version);
+        self.root.join(major_version).join(full_version)
+    }
+
+    /// Every processed version, i.e. every version directory with an original.txt, oldest first.
+    pub fn versions(&self) -> Result<Vec<String>> {
+        let mut versions: Vec<String> = Vec::new();
+
+        if !self.root.exists() {
+            return Ok(versions);
+        }
+
+        for major in fs::read_dir(&self.root)? {
+            let major = major?;
+            // Hidden directories, like the staging directory of a run in progress, hold no versions
+            if !major.file_type()?.is_dir() || major.file_name().to_string_lossy().starts_with('.')
+            {
+                continue;
+            }
+
+            for version in fs::read_dir(major.path())? {
+                let version = version?;
+                if version.file_type()?.is_dir() && version.path().join("original.txt").is_file() {
+                    versions.push(version.file_name().to_string_lossy().into_owned());
+                }
+            }
+        }
+
+        versions.sort_by(|a, b| compare_versions(a, b));
+        Ok(versions)
+    }
+
+    /// Whether the run that processed `full_version` finished, rather than just got as far as its directory.
+    /// Directories from before the marker existed only count as complete when they also have the
+    /// added.txt and removed.txt the old pipeline wrote after original.txt.
+    pub fn is_complete(&self, full_version: &str) -> bool {
+        let version_dir: PathBuf = self.version_dir(full_version);
+        if version_dir.join(COMPLETE_MARKER).is_file() {
+            return true;
+        }
+
+        LEGACY_COMPLETE_FILES
+            .iter()
+            .all(|file| version_dir.join(file).is_file())
+    }
+
+    /// Records that every output of `full_version` is in place, with the time it was.
+    pub fn mark_complete(&self, full_version: &str) -> Result<()> {
+        fs::write(
+            self.version_dir(full_version).join(COMPLETE_MARKER),
+            Utc::now().to_rfc3339(),
+        )?;
+        Ok(())
+    }
+
+    pub fn latest_version(&self) -> Result<Option<String>> {
+        Ok(self.versions()?.pop())
+    }
+
+    /// The given version after checking it was processed, or the latest processed one.
+    pub fn version_or_latest(&self, full_version: Option<String>) -> Result<String> {
+        let full_version: String = match full_version {
+            Some(full_version) => full_version,
+            None => self
+                .latest_version()?
+                .ok_or_else(|| anyhow!("No processed versions in {}", self.root.display()))?,
+        };
+        self.require_version(&full_version)?;
+
+        Ok(full_version)
+    }
+
+    /// Fails with a clear message when `full_version` was never processed.
+    pub fn require_version(&self, full_version: &str) -> Result<()> {
+        if self
+            .version_dir(full_version)
+            .join("original.txt")
+            .is_file()
+        {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Version {} is not in {}",
+                full_version,
+                self.root.display()
+            ))
+        }
+    }
+
+    /// Reads a name list saved for a version, or None when that version has no such file.
+    /// Blank lines and "#" headings are skipped, so sectioned lists like added.txt read as one list.
+    pub fn read_list(&self, full_version: &str, filename: &str) -> Result<Option<Vec<String>>> {
+        let file_path: PathBuf = self.version_dir(full_version).join(filename);
+
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let content: String = fs::read_to_string(file_path)?;
+
+        Ok(Some(
+            content
+                .lines()
+                .map(|line| line.trim_end().to_string())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn only_marked_or_fully_diffed_versions_are_complete() {
+        let root: TempDir = tempfile::tempdir().unwrap();
+        let archive: Archive = Archive::new(root.path().to_path_buf());
+
+        // A run that died right after saving the features
+        let interrupted: PathBuf = archive.version_dir("150.0.1.0");
+        fs::create_dir_all(&interrupted).unwrap();
+        fs::write(interrupted.join("original.txt"), "A").unwrap();
+        assert!(!archive.is_complete("150.0.1.0"));
+
+        // A run from before the marker that got through the diff
+        let legacy: PathBuf = archive.version_dir("150.0.2.0");
+        fs::create_dir_all(&legacy).unwrap();
+        for file in LEGACY_COMPLETE_FILES {
+            fs::write(legacy.join(file), "A").unwrap();
+        }
+        assert!(archive.is_complete("150.0.2.0"));
+
+        archive.mark_complete("150.0.1.0").unwrap();
+        assert!(archive.is_complete("150.0.1.0"));
+        assert!(!archive.is_complete("150.0.3.0"));
+    }
+}
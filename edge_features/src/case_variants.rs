@@ -0,0 +1,124 @@
+use crate::{ListDiff, diff_sets};
+use std::collections::{BTreeMap, HashSet};
+
+/// The key that spellings of a name differing only in case share, e.g. "msedge" for MSEdge and msEdge.
+pub fn canonical_key(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
+
+/// Groups names under their canonical key, each group's spellings sorted.
+pub fn group<'a>(names: impl IntoIterator<Item = &'a String>) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for name in names {
+        groups
+            .entry(canonical_key(name))
+            .or_default()
+            .push(name.clone());
+    }
+
+    for spellings in groups.values_mut() {
+        spellings.sort();
+    }
+
+    groups
+}
+
+/// One line per canonical key that has more than one spelling: "msedge: MSEDGE, MSEdge, msEdge".
+pub fn alias_lines(names: &HashSet<String>) -> Vec<String> {
+    group(names)
+        .into_iter()
+        .filter(|(_, spellings)| spellings.len() > 1)
+        .map(|(key, spellings)| format!("{}: {}", key, spellings.join(", ")))
+        .collect()
+}
+
+/// A name present in both builds whose spellings changed, only in case.
+#[derive(Clone, Debug)]
+pub struct CaseChange {
+    pub previous: Vec<String>,
+    pub current: Vec<String>,
+}
+
+impl CaseChange {
+    pub fn label(&self) -> String {
+        format!("{} → {}", self.previous.join(", "), self.current.join(", "))
+    }
+}
+
+/// Like `diff_sets`, but a name only counts as added or removed when no spelling of it was there
+/// before or is left after. Names that are still there under different casing are case changes.
+pub fn diff(current: &HashSet<String>, previous: &HashSet<String>) -> (ListDiff, Vec<CaseChange>) {
+    let ListDiff { added, removed } = diff_sets(current, previous);
+
+    let current_groups: BTreeMap<String, Vec<String>> = group(current);
+    let previous_groups: BTreeMap<String, Vec<String>> = group(previous);
+
+    let (case_added, added): (Vec<String>, Vec<String>) = added
+        .into_iter()
+        .partition(|name| previous_groups.contains_key(&canonical_key(name)));
+    let (case_removed, removed): (Vec<String>, Vec<String>) = removed
+        .into_iter()
+        .partition(|name| current_groups.contains_key(&canonical_key(name)));
+
+    let changed_keys: HashSet<String> = case_added
+        .iter()
+        .chain(case_removed.iter())
+        .map(|name| canonical_key(name))
+        .collect();
+
+    let mut case_changes: Vec<CaseChange> = changed_keys
+        .into_iter()
+        .map(|key| CaseChange {
+            previous: previous_groups[&key].clone(),
+            current: current_groups[&key].clone(),
+        })
+        .collect();
+    case_changes.sort_by(|a, b| a.previous.cmp(&b.previous));
+
+    (ListDiff { added, removed }, case_changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn keeps_case_only_changes_out_of_added_and_removed() {
+        let previous: HashSet<String> = set(&["msEdgeFoo", "msOld", "msKept"]);
+        let current: HashSet<String> = set(&["MSEdgeFoo", "msNew", "msKept"]);
+
+        let (ListDiff { added, removed }, case_changes): (ListDiff, Vec<CaseChange>) =
+            diff(&current, &previous);
+
+        assert_eq!(added, ["msNew"]);
+        assert_eq!(removed, ["msOld"]);
+        assert_eq!(case_changes.len(), 1);
+        assert_eq!(case_changes[0].label(), "msEdgeFoo → MSEdgeFoo");
+    }
+
+    #[test]
+    fn reports_a_spelling_added_next_to_an_existing_one() {
+        let previous: HashSet<String> = set(&["msEdgeFoo"]);
+        let current: HashSet<String> = set(&["msEdgeFoo", "MSEdgeFoo"]);
+
+        let (ListDiff { added, removed }, case_changes): (ListDiff, Vec<CaseChange>) =
+            diff(&current, &previous);
+
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+        assert_eq!(case_changes[0].label(), "msEdgeFoo → MSEdgeFoo, msEdgeFoo");
+    }
+
+    #[test]
+    fn lists_only_names_with_several_spellings_as_aliases() {
+        assert_eq!(
+            alias_lines(&set(&["msEdge", "MSEdge", "msOther"])),
+            ["msedge: MSEdge, msEdge"]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::Result;
+use regex::Regex;
+
+/// A kind of string tracked per build, with its own patterns and per-version files.
+pub struct StringCategory {
+    /// Short identifier used in console output
+    pub name: &'static str,
+    /// Plural heading used in the release notes, e.g. "Command-line switches"
+    pub title: &'static str,
+    /// A string belongs to the category when any of these match it in full
+    pub patterns: &'static [&'static str],
+    /// Turns a matched string into the name that is saved and diffed
+    pub normalize: fn(&str) -> String,
+    /// Full list of names found in a build
+    pub list_file: &'static str,
+    pub added_file: &'static str,
+    pub removed_file: &'static str,
+}
+
+impl StringCategory {
+    pub fn matcher(&'static self) -> Result<CategoryMatcher> {
+        let regexes: Vec<Regex> = self
+            .patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<Result<Vec<Regex>, regex::Error>>()?;
+
+        Ok(CategoryMatcher {
+            category: self,
+            regexes,
+        })
+    }
+}
+
+/// A category with its patterns compiled.
+pub struct CategoryMatcher {
+    pub category: &'static StringCategory,
+    regexes: Vec<Regex>,
+}
+
+impl CategoryMatcher {
+    /// Returns the normalized name when `text` belongs to this category.
+    pub fn match_text(&self, text: &str) -> Option<String> {
+        self.regexes
+            .iter()
+            .any(|regex| regex.is_match(text))
+            .then(|| (self.category.normalize)(text))
+    }
+}
+
+fn keep(text: &str) -> String {
+    text.to_string()
+}
+
+// Switches are stored with or without the leading dashes; always report them as "--name"
+fn normalize_switch(text: &str) -> String {
+    format!("--{}", text.trim_start_matches('-'))
+}
+
+// Only the page matters, not the sub-path or fragment inside it
+fn normalize_internal_url(text: &str) -> String {
+    let host: &str = text
+        .trim_start_matches("edge://")
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    format!("edge://{}", host.to_ascii_lowercase())
+}
+
+// Policies are referenced both bare and as a value under the Edge policy key
+fn normalize_policy(text: &str) -> String {
+    text.rsplit('\\').next().unwrap_or(text).to_string()
+}
+
+/// Feature flags keep their historical file names so older builds stay comparable.
+pub const FEATURES: StringCategory = StringCategory {
+    name: "features",
+    title: "Features",
+    // Runs that start with "ms" (case-Insensitive) followed by at least 4 alphanumeric characters
+    patterns: &[r"(?i)^ms[a-zA-Z0-9]{4,}$"],
+    normalize: keep,
+    list_file: "original.txt",
+    added_file: "added.txt",
+    removed_file: "removed.txt",
+};
+
+pub const SWITCHES: StringCategory = StringCategory {
+    name: "switches",
+    title: "Command-line switches",
+    patterns: &[
+        r"^(?:--)?ms-[a-z0-9]+(?:-[a-z0-9]+)*$",
+        r"^(?:--)?edge-[a-z0-9]+(?:-[a-z0-9]+)*$",
+    ],
+    normalize: normalize_switch,
+    list_file: "switches.txt",
+    added_file: "switches_added.txt",
+    removed_file: "switches_removed.txt",
+};
+
+pub const INTERNAL_URLS: StringCategory = StringCategory {
+    name: "internal URLs",
+    title: "Internal edge:// pages",
+    patterns: &[r"^edge://[a-z0-9-]+(?:[/?#][\x21-\x7e]*)?$"],
+    normalize: normalize_internal_url,
+    list_file: "internal_urls.txt",
+    added_file: "internal_urls_added.txt",
+    removed_file: "internal_urls_removed.txt",
+};
+
+pub const POLICIES: StringCategory = StringCategory {
+    name: "policy names",
+    title: "Policy names",
+    // Policy names are PascalCase and by convention end with one of a handful of suffixes
+    patterns: &[
+        r"^(?:(?i:software\\policies\\microsoft\\edge)\\)?[A-Z][a-z0-9]+(?:[A-Z][a-z0-9]*)*(?:Enabled|Allowed|AllowList|BlockList|Blocked|Mode|Policy|Settings|Level|Url|Urls)$",
+    ],
+    normalize: normalize_policy,
+    list_file: "policies.txt",
+    added_file: "policies_added.txt",
+    removed_file: "policies_removed.txt",
+};
+
+pub const PREFS: StringCategory = StringCategory {
+    name: "pref paths",
+    title: "Pref paths",
+    patterns: &[r"^(?:edge|browser|profile|ntp)\.[a-z0-9_]+(?:\.[a-z0-9_]+)*$"],
+    normalize: keep,
+    list_file: "prefs.txt",
+    added_file: "prefs_added.txt",
+    removed_file: "prefs_removed.txt",
+};
+
+/// Every category extracted from a build, features first.
+pub const CATEGORIES: &[&StringCategory] =
+    &[&FEATURES, &SWITCHES, &INTERNAL_URLS, &POLICIES, &PREFS];
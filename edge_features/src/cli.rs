@@ -0,0 +1,170 @@
+use crate::diff::DiffFormat;
+use crate::publish::PublisherKind;
+use crate::strings::DEFAULT_MIN_LENGTH;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(about = "Finds the features added and removed in each Edge Canary build")]
+pub struct Cli {
+    /// Config file, TOML or JSON; edge_features.toml in the working directory is used when it exists
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Directory that holds the processed builds, overrides the config file [default: Edge Canary]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Do not commit, push or create a GitHub release, same as --publisher none
+    #[arg(long, global = true, conflicts_with = "publisher")]
+    pub no_publish: bool,
+
+    /// Where `update` publishes a new build, overrides the config file [default: github]
+    #[arg(long, global = true, value_name = "KIND")]
+    pub publisher: Option<PublisherKind>,
+
+    /// Shortest string run the scanner keeps
+    #[arg(long, global = true, value_name = "CHARS", default_value_t = DEFAULT_MIN_LENGTH)]
+    pub min_length: usize,
+
+    /// Scan every section of msedge.dll instead of only the read-only data sections
+    #[arg(long, global = true)]
+    pub all_sections: bool,
+
+    /// What to do, `update` when omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Process the latest Edge Canary build and publish the results. A build that was already
+    /// processed only gets its release published, as `republish` does
+    Update {
+        /// Run the Canary setup and scan the installed build instead of unpacking the offline installer package
+        #[arg(long)]
+        install: bool,
+    },
+
+    /// Process an already extracted msedge.dll; nothing is committed, pushed or released
+    Scan {
+        /// Path to msedge.dll
+        dll: PathBuf,
+
+        /// Version of the DLL, read from its version resource when omitted
+        #[arg(long, value_name = "VERSION")]
+        edge_version: Option<String>,
+
+        /// Process the DLL even if its version was already processed
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show the features added and removed between any two processed versions, across majors too
+    Diff {
+        from: String,
+        to: String,
+
+        #[arg(long, value_enum, default_value = "text")]
+        format: DiffFormat,
+
+        /// Write the diff to a file instead of printing it
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+
+    /// Show when a feature was first and last seen, every time it was added or removed, and whether it is still present
+    History { feature: String },
+
+    /// List the features of a processed version that match a regular expression
+    Search {
+        pattern: String,
+
+        /// Version to search, the latest processed one when omitted
+        #[arg(long, value_name = "VERSION")]
+        version: Option<String>,
+    },
+
+    /// List a family of related features, every name starting with the given prefix, and write the
+    /// shortcut maker and the launchers for every platform that enable the whole family
+    Family {
+        /// Name prefix such as msToolbarDeclutter, matched ignoring case
+        name: String,
+
+        /// Version to take the family from, the latest processed one when omitted
+        #[arg(long, value_name = "VERSION")]
+        version: Option<String>,
+
+        /// Directory to write the launchers to
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        output_dir: PathBuf,
+    },
+
+    /// Turn on edge://flags entries by writing them into browser.enabled_labs_experiments in Edge's
+    /// Local State. Edge drops anything else from that list at startup, so features without a flags
+    /// entry are enabled with the launchers instead. Close Edge first, it writes the file back when it exits
+    EnableFlags {
+        /// edge://flags entry names, e.g. flag-name, or flag-name@N for an option other than the first
+        #[arg(required = true, value_name = "FLAG")]
+        flags: Vec<String>,
+
+        /// Local State file of the Edge user data directory to change, Edge Canary's when omitted
+        #[arg(long, value_name = "PATH")]
+        local_state: Option<PathBuf>,
+
+        /// Only show which flags would be added
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Put the Local State file back the way it was before the last enable-flags
+    UndoEnableFlags {
+        /// Local State file of the Edge user data directory, Edge Canary's when omitted
+        #[arg(long, value_name = "PATH")]
+        local_state: Option<PathBuf>,
+    },
+
+    /// Write the shortcut maker and the launchers for every platform with the features added in a
+    /// version, plus any features, field trials and profile directory given here
+    Launcher {
+        /// Version whose added features are enabled, the latest processed one when omitted
+        #[arg(long, value_name = "VERSION")]
+        version: Option<String>,
+
+        /// Enable every feature added after this version that is still in --version, for catching up
+        /// on several skipped updates at once
+        #[arg(long, value_name = "VERSION")]
+        since: Option<String>,
+
+        /// More features to enable, comma-separated
+        #[arg(long, value_name = "FEATURES", value_delimiter = ',')]
+        enable: Vec<String>,
+
+        /// Features to disable, comma-separated; they are taken out of the enabled ones
+        #[arg(long, value_name = "FEATURES", value_delimiter = ',')]
+        disable: Vec<String>,
+
+        /// Value for --force-fieldtrials, e.g. "Trial/Group/"
+        #[arg(long, value_name = "TRIALS")]
+        force_fieldtrials: Option<String>,
+
+        /// Run Edge with a separate profile directory instead of the everyday one
+        #[arg(long, value_name = "PATH")]
+        user_data_dir: Option<String>,
+
+        /// Directory to write the launchers to
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        output_dir: PathBuf,
+    },
+
+    /// Regenerate the README block from the last processed version
+    RebuildReadme,
+
+    /// Create or update the release of a processed version from its files in the archive, e.g.
+    /// after a run that pushed the build but died before its release was published. Nothing is
+    /// committed or pushed
+    Republish {
+        /// Full version, e.g. 140.0.3456.0
+        version: String,
+    },
+}
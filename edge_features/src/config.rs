@@ -0,0 +1,226 @@
+use crate::publish::PublisherKind;
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Read from the working directory when no --config is given.
+pub const DEFAULT_CONFIG_FILE: &str = "edge_features.toml";
+
+/// Everything that differs between this repository and a fork of it. Every field has a default,
+/// so a config file only needs the ones it changes.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct Config {
+    /// Directory that holds the processed builds
+    pub data_dir: PathBuf,
+    pub repository: RepositoryConfig,
+    pub git: GitConfig,
+    pub downloads: DownloadConfig,
+    pub images: ImageConfig,
+    pub publish: PublishConfig,
+}
+
+/// The GitHub repository commits are pushed to and releases are created in.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct RepositoryConfig {
+    pub owner: String,
+    pub name: String,
+    /// Web address of the GitHub instance, for links in the README and release notes
+    pub web_url: String,
+}
+
+/// Identity of the automated commits.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct GitConfig {
+    pub user_name: String,
+    pub user_email: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct DownloadConfig {
+    /// Versions endpoint of the Edge update API that offers the Canary offline installer package
+    pub package_api: String,
+    /// Where the Canary setup is downloaded from for --install, tried in order
+    pub installer_urls: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct ImageConfig {
+    /// Logo at the top of the README block and the release notes
+    pub logo: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct PublishConfig {
+    /// Where `update` publishes a new build, unless --publisher or --no-publish says otherwise
+    pub publisher: PublisherKind,
+    /// REST API of the GitHub instance releases are created through
+    pub api_base_url: String,
+    /// Directory the filesystem publisher writes releases to
+    pub output_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_dir: PathBuf::from("Edge Canary"),
+            repository: RepositoryConfig::default(),
+            git: GitConfig::default(),
+            downloads: DownloadConfig::default(),
+            images: ImageConfig::default(),
+            publish: PublishConfig::default(),
+        }
+    }
+}
+
+impl Default for RepositoryConfig {
+    fn default() -> Self {
+        Self {
+            owner: "SpyNetGirl".to_string(),
+            name: "MSEdgeFeatures".to_string(),
+            web_url: "https://github.com".to_string(),
+        }
+    }
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            user_name: "HotCakeX".to_string(),
+            user_email: "spynetgirl@outlook.com".to_string(),
+        }
+    }
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            package_api: "https://msedge.api.cdp.microsoft.com/api/v1.1/internal/contents/Browser/namespaces/Default/names/msedge-canary-win-x64/versions".to_string(),
+            installer_urls: vec![
+                "https://go.microsoft.com/fwlink/?linkid=2084706&Channel=Canary&language=en".to_string(),
+                "https://c2rsetup.edog.officeapps.live.com/c2r/downloadEdge.aspx?platform=Default&source=EdgeInsiderPage&Channel=Canary&language=en".to_string(),
+            ],
+        }
+    }
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            logo: "https://github.com/HotCakeX/Harden-Windows-Security/raw/main/images/WebP/Edge%20Canary.webp".to_string(),
+        }
+    }
+}
+
+impl Default for PublishConfig {
+    fn default() -> Self {
+        Self {
+            publisher: PublisherKind::Github,
+            api_base_url: "https://api.github.com".to_string(),
+            output_dir: PathBuf::from("releases"),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file, TOML or JSON by its extension, then applies the environment overrides.
+    /// Without an explicit `path`, edge_features.toml is used when it exists and the defaults otherwise.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path: Option<&Path> =
+            path.or_else(|| Some(Path::new(DEFAULT_CONFIG_FILE)).filter(|path| path.is_file()));
+
+        let mut config: Config = match path {
+            Some(path) => {
+                let content: String = fs::read_to_string(path)
+                    .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "json")
+                {
+                    serde_json::from_str(&content)
+                        .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?
+                } else {
+                    toml::from_str(&content)
+                        .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?
+                }
+            }
+            None => Config::default(),
+        };
+
+        config.apply_env_overrides()?;
+        Ok(config)
+    }
+
+    // EDGE_FEATURES_* variables win over the file, so CI can change a setting without committing it
+    fn apply_env_overrides(&mut self) -> Result<()> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        if let Some(value) = var("EDGE_FEATURES_DATA_DIR") {
+            self.data_dir = PathBuf::from(value);
+        }
+        if let Some(value) = var("EDGE_FEATURES_REPO_OWNER") {
+            self.repository.owner = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_REPO_NAME") {
+            self.repository.name = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_REPO_WEB_URL") {
+            self.repository.web_url = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_GIT_USER_NAME") {
+            self.git.user_name = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_GIT_USER_EMAIL") {
+            self.git.user_email = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_PACKAGE_API") {
+            self.downloads.package_api = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_INSTALLER_URLS") {
+            self.downloads.installer_urls = value.split(',').map(String::from).collect();
+        }
+        if let Some(value) = var("EDGE_FEATURES_LOGO") {
+            self.images.logo = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_PUBLISHER") {
+            self.publish.publisher = PublisherKind::from_str(&value, true)
+                .map_err(|e| anyhow!("Invalid EDGE_FEATURES_PUBLISHER: {}", e))?;
+        }
+        if let Some(value) = var("EDGE_FEATURES_API_BASE_URL") {
+            self.publish.api_base_url = value;
+        }
+        if let Some(value) = var("EDGE_FEATURES_PUBLISH_DIR") {
+            self.publish.output_dir = PathBuf::from(value);
+        }
+
+        Ok(())
+    }
+
+    /// The repository's page, e.g. https://github.com/SpyNetGirl/MSEdgeFeatures
+    pub fn repository_url(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.repository.web_url.trim_end_matches('/'),
+            self.repository.owner,
+            self.repository.name
+        )
+    }
+
+    /// Where an asset of the release tagged `tag` can be downloaded from.
+    pub fn release_asset_url(&self, tag: &str, asset_name: &str) -> String {
+        format!(
+            "{}/releases/download/{}/{}",
+            self.repository_url(),
+            tag,
+            asset_name
+        )
+    }
+}
@@ -0,0 +1,103 @@
+use crate::archive::Archive;
+use crate::case_variants::{self, CaseChange};
+use crate::{ListDiff, markdown_list};
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum DiffFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+/// The features added and removed between any two processed versions.
+#[derive(Serialize, Debug)]
+pub struct VersionDiff {
+    pub from: String,
+    pub to: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Names present in both versions that are spelled with different casing
+    pub case_changed: Vec<String>,
+}
+
+/// Compares the original.txt of two versions, which may belong to different majors.
+pub fn diff_versions(archive: &Archive, from: &str, to: &str) -> Result<VersionDiff> {
+    archive.require_version(from)?;
+    archive.require_version(to)?;
+
+    let load = |version: &str| -> Result<HashSet<String>> {
+        Ok(archive
+            .read_list(version, "original.txt")?
+            .unwrap_or_default()
+            .into_iter()
+            .collect())
+    };
+
+    let (ListDiff { added, removed }, case_changed): (ListDiff, Vec<CaseChange>) =
+        case_variants::diff(&load(to)?, &load(from)?);
+
+    Ok(VersionDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        added,
+        removed,
+        case_changed: case_changed.iter().map(CaseChange::label).collect(),
+    })
+}
+
+impl VersionDiff {
+    pub fn render(&self, format: DiffFormat) -> Result<String> {
+        Ok(match format {
+            DiffFormat::Text => {
+                let mut lines: Vec<String> = vec![format!(
+                    "{} features were added from {} to {}",
+                    self.added.len(),
+                    self.from,
+                    self.to
+                )];
+                lines.extend(self.added.iter().map(|feature| format!("+ {}", feature)));
+                lines.push(format!(
+                    "{} features were removed from {} to {}",
+                    self.removed.len(),
+                    self.from,
+                    self.to
+                ));
+                lines.extend(self.removed.iter().map(|feature| format!("- {}", feature)));
+                lines.push(format!(
+                    "{} features only changed case from {} to {}",
+                    self.case_changed.len(),
+                    self.from,
+                    self.to
+                ));
+                lines.extend(
+                    self.case_changed
+                        .iter()
+                        .map(|change| format!("~ {}", change)),
+                );
+                lines.join("\n")
+            }
+            DiffFormat::Markdown => format!(
+                "# Edge Canary {} → {}\n\n\
+                ### {} New features were added\n\n\
+                {}\n\
+                ### {} Features were removed\n\n\
+                {}\n\
+                ### {} Features only changed case\n\n\
+                {}",
+                self.from,
+                self.to,
+                self.added.len(),
+                markdown_list(&self.added),
+                self.removed.len(),
+                markdown_list(&self.removed),
+                self.case_changed.len(),
+                markdown_list(&self.case_changed)
+            ),
+            DiffFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+}
@@ -0,0 +1,116 @@
+use crate::case_variants::canonical_key;
+use crate::renames::split_camel_case;
+use std::collections::{BTreeMap, HashSet};
+
+/// Camel-case tokens that make up a family name, the "ms" prefix included: msToolbarDeclutter.
+const FAMILY_TOKENS: usize = 3;
+
+/// The family a name belongs to, its first camel-case tokens, or None for names too short to have one.
+pub fn family_of(name: &str) -> Option<&str> {
+    let parts: Vec<&str> = split_camel_case(name);
+    if parts.len() < FAMILY_TOKENS {
+        return None;
+    }
+
+    let length: usize = parts[..FAMILY_TOKENS].iter().map(|part| part.len()).sum();
+    Some(&name[..length])
+}
+
+/// Related flags sharing a name prefix, which often have to be enabled together.
+#[derive(Clone, Debug)]
+pub struct Family {
+    pub name: String,
+    pub members: Vec<String>,
+}
+
+/// Clusters names by family, keeping only families with at least two members. Spellings that
+/// differ in case land in the same family, which is named after its first member.
+pub fn families(names: &HashSet<String>) -> BTreeMap<String, Family> {
+    let mut sorted: Vec<&String> = names.iter().collect();
+    sorted.sort();
+
+    let mut families: BTreeMap<String, Family> = BTreeMap::new();
+    for name in sorted {
+        let Some(family) = family_of(name) else {
+            continue;
+        };
+
+        families
+            .entry(canonical_key(family))
+            .or_insert_with(|| Family {
+                name: family.to_string(),
+                members: Vec::new(),
+            })
+            .members
+            .push(name.clone());
+    }
+
+    families.retain(|_, family| family.members.len() > 1);
+    families
+}
+
+/// A family that gained members in a build.
+#[derive(Clone, Debug)]
+pub struct FamilyGrowth {
+    pub family: String,
+    pub added: Vec<String>,
+    /// Members in the build, the added ones included
+    pub size: usize,
+}
+
+impl FamilyGrowth {
+    pub fn label(&self) -> String {
+        format!(
+            "{} family: +{} ({}), {} members",
+            self.family,
+            self.added.len(),
+            self.added.join(", "),
+            self.size
+        )
+    }
+}
+
+/// The families of `current` that `added` gave new members, largest gain first.
+pub fn growth(current: &HashSet<String>, added: &[String]) -> Vec<FamilyGrowth> {
+    let added: HashSet<&String> = added.iter().collect();
+
+    let mut grown: Vec<FamilyGrowth> = families(current)
+        .into_values()
+        .filter_map(|family| {
+            let new_members: Vec<String> = family
+                .members
+                .iter()
+                .filter(|member| added.contains(member))
+                .cloned()
+                .collect();
+
+            (!new_members.is_empty()).then_some(FamilyGrowth {
+                family: family.name,
+                added: new_members,
+                size: family.members.len(),
+            })
+        })
+        .collect();
+
+    grown.sort_by(|a, b| {
+        b.added
+            .len()
+            .cmp(&a.added.len())
+            .then_with(|| a.family.cmp(&b.family))
+    });
+    grown
+}
+
+/// Every name that starts with `family`, ignoring case, sorted. Any prefix works, not just the
+/// ones `families` finds, so a broader or narrower group can be picked too.
+pub fn members(names: &[String], family: &str) -> Vec<String> {
+    let prefix: String = canonical_key(family);
+
+    let mut members: Vec<String> = names
+        .iter()
+        .filter(|name| canonical_key(name).starts_with(&prefix))
+        .cloned()
+        .collect();
+    members.sort();
+    members
+}
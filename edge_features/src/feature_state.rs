@@ -0,0 +1,141 @@
+use crate::pe::PeFile;
+use std::collections::HashMap;
+
+/// Mirrors Chromium's `base::FeatureState` enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeatureState {
+    DisabledByDefault,
+    EnabledByDefault,
+}
+
+impl FeatureState {
+    fn from_raw(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::DisabledByDefault),
+            1 => Some(Self::EnabledByDefault),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DisabledByDefault => "disabled",
+            Self::EnabledByDefault => "enabled",
+        }
+    }
+}
+
+/// Finds `base::Feature` objects, laid out as `{const char* name, FeatureState default_state}`,
+/// whose name pointer targets one of `names` (keyed by RVA), and reads their default state.
+///
+/// A name is only resolved when every candidate object agrees on the state, so incidental
+/// pointers to the same string that happen to be followed by 0 or 1 do not produce a guess.
+pub fn resolve_default_states(
+    data: &[u8],
+    pe: &PeFile,
+    names: &HashMap<u32, String>,
+) -> HashMap<String, FeatureState> {
+    let pointer_size: usize = pe.pointer_size();
+    let mut votes: HashMap<&str, (usize, usize)> = HashMap::new();
+
+    for section in pe.sections.iter().filter(|s| s.is_data()) {
+        let range: std::ops::Range<usize> = section.raw_range(data.len());
+
+        // Statics are pointer aligned, so only aligned slots can start a base::Feature
+        let mut offset: usize = range.start.next_multiple_of(pointer_size);
+        while offset + pointer_size + 4 <= range.end {
+            let Ok(pointer) = pe.read_pointer(data, offset) else {
+                break;
+            };
+
+            let name: Option<&String> = pointer
+                .checked_sub(pe.image_base)
+                .and_then(|rva| u32::try_from(rva).ok())
+                .and_then(|rva| names.get(&rva));
+
+            if let Some(name) = name {
+                let raw_state: u32 = u32::from_le_bytes([
+                    data[offset + pointer_size],
+                    data[offset + pointer_size + 1],
+                    data[offset + pointer_size + 2],
+                    data[offset + pointer_size + 3],
+                ]);
+
+                if let Some(state) = FeatureState::from_raw(raw_state) {
+                    let entry: &mut (usize, usize) = votes.entry(name.as_str()).or_default();
+                    match state {
+                        FeatureState::DisabledByDefault => entry.0 += 1,
+                        FeatureState::EnabledByDefault => entry.1 += 1,
+                    }
+                }
+            }
+
+            offset += pointer_size;
+        }
+    }
+
+    votes
+        .into_iter()
+        .filter_map(|(name, (disabled, enabled))| match (disabled, enabled) {
+            (0, 0) => None,
+            (_, 0) => Some((name.to_string(), FeatureState::DisabledByDefault)),
+            (0, _) => Some((name.to_string(), FeatureState::EnabledByDefault)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::tests::{put_u32, synthetic_pe};
+
+    // Where the synthetic image's .rdata starts in the file and in memory
+    const RDATA_OFFSET: usize = 0x400;
+    const RDATA_RVA: u32 = 0x2000;
+
+    // A base::Feature at `offset` of the synthetic image whose name pointer targets `name_rva`
+    fn put_feature(data: &mut [u8], pe: &PeFile, offset: usize, name_rva: u32, state: u32) {
+        let pointer: u64 = pe.image_base + u64::from(name_rva);
+        data[offset..offset + 8].copy_from_slice(&pointer.to_le_bytes());
+        put_u32(data, offset + 8, state);
+    }
+
+    #[test]
+    fn reads_the_state_only_when_every_object_agrees() {
+        let mut data: Vec<u8> = synthetic_pe();
+        let pe: PeFile = PeFile::parse(&data).unwrap();
+
+        let names: HashMap<u32, String> = ["msAlpha", "msBravo", "msCharlie", "msDelta"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (RDATA_RVA + index as u32 * 0x10, name.to_string()))
+            .collect();
+
+        put_feature(&mut data, &pe, RDATA_OFFSET + 0x40, RDATA_RVA, 1);
+        put_feature(&mut data, &pe, RDATA_OFFSET + 0x50, RDATA_RVA + 0x10, 0);
+        // Two objects that disagree leave the state unknown
+        put_feature(&mut data, &pe, RDATA_OFFSET + 0x60, RDATA_RVA + 0x20, 0);
+        put_feature(&mut data, &pe, RDATA_OFFSET + 0x70, RDATA_RVA + 0x20, 1);
+        // A pointer followed by something that is not a FeatureState is not a base::Feature
+        put_feature(&mut data, &pe, RDATA_OFFSET + 0x80, RDATA_RVA + 0x30, 7);
+
+        let states: HashMap<String, FeatureState> = resolve_default_states(&data, &pe, &names);
+
+        assert_eq!(states.len(), 2);
+        assert_eq!(states["msAlpha"], FeatureState::EnabledByDefault);
+        assert_eq!(states["msBravo"], FeatureState::DisabledByDefault);
+    }
+
+    #[test]
+    fn ignores_objects_outside_the_data_sections() {
+        let mut data: Vec<u8> = synthetic_pe();
+        let pe: PeFile = PeFile::parse(&data).unwrap();
+        let names: HashMap<u32, String> = HashMap::from([(RDATA_RVA, "msAlpha".to_string())]);
+
+        // Past the end of .rdata's virtual size, so not part of the section
+        put_feature(&mut data, &pe, RDATA_OFFSET + 0x100, RDATA_RVA, 1);
+
+        assert!(resolve_default_states(&data, &pe, &names).is_empty());
+    }
+}
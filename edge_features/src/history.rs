@@ -0,0 +1,289 @@
+use crate::archive::Archive;
+use crate::compare_versions;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// File in the archive root the whole history index is saved to.
+pub const HISTORY_FILE: &str = "history.json";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Added,
+    Removed,
+}
+
+impl EventKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Added => "added",
+            EventKind::Removed => "removed",
+        }
+    }
+}
+
+/// A feature appearing in or disappearing from a build compared to the build before it.
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryEvent {
+    pub version: String,
+    pub kind: EventKind,
+    /// For an addition of a feature that was removed before, the last version that still had it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<String>,
+    /// For a removal, whether a later build added the feature back
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub temporary: bool,
+}
+
+/// The lifetime of one feature across every processed version. Only what a build that adds or
+/// removes the feature changes is kept, so a saved index does not churn with every build.
+#[derive(Serialize, Debug)]
+pub struct FeatureHistory {
+    pub first_seen: String,
+    /// The last version that had the feature, None while the latest processed version still has it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<String>,
+    pub events: Vec<HistoryEvent>,
+}
+
+impl FeatureHistory {
+    /// Whether the latest processed version still has the feature.
+    pub fn present(&self) -> bool {
+        self.last_seen.is_none()
+    }
+}
+
+/// A feature a build added back after an earlier build removed it.
+#[derive(Clone, Debug)]
+pub struct Reintroduction {
+    pub feature: String,
+    pub last_seen: String,
+    pub removed_in: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct HistoryIndex {
+    /// Changes with every build, so it is worked out from the archive rather than saved
+    #[serde(skip)]
+    pub latest_version: Option<String>,
+    pub features: BTreeMap<String, FeatureHistory>,
+}
+
+/// Walks the original.txt of every processed version, oldest first, and records when each
+/// feature came and went. The first archived version counts as adding everything it has.
+pub fn build(archive: &Archive) -> Result<HistoryIndex> {
+    build_from(archive, None)
+}
+
+/// Like `build`, with `features` standing in for the original.txt of `version`, which may not
+/// have been moved into the archive yet.
+pub fn build_including(
+    archive: &Archive,
+    version: &str,
+    features: &HashSet<String>,
+) -> Result<HistoryIndex> {
+    build_from(archive, Some((version, features)))
+}
+
+fn build_from(archive: &Archive, staged: Option<(&str, &HashSet<String>)>) -> Result<HistoryIndex> {
+    let mut versions: Vec<String> = archive.versions()?;
+    if let Some((staged_version, _)) = staged
+        && !versions.iter().any(|version| version == staged_version)
+    {
+        versions.push(staged_version.to_string());
+        versions.sort_by(|a, b| compare_versions(a, b));
+    }
+
+    let mut features: BTreeMap<String, FeatureHistory> = BTreeMap::new();
+    let mut previous: HashSet<String> = HashSet::new();
+
+    for (position, version) in versions.iter().enumerate() {
+        let current: HashSet<String> = match staged {
+            Some((staged_version, staged_features)) if staged_version == version => {
+                staged_features.clone()
+            }
+            _ => archive
+                .read_list(version, "original.txt")?
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        };
+
+        // Only changes are recorded, last_seen is settled when a feature goes away
+        for feature in current.difference(&previous) {
+            let history: &mut FeatureHistory =
+                features
+                    .entry(feature.clone())
+                    .or_insert_with(|| FeatureHistory {
+                        first_seen: version.clone(),
+                        last_seen: None,
+                        events: Vec::new(),
+                    });
+
+            // A known feature missing from the previous build was removed, so that removal did not stick
+            if let Some(removal) = history.events.last_mut() {
+                removal.temporary = true;
+            }
+            let last_seen: Option<String> = history.last_seen.take();
+
+            history.events.push(HistoryEvent {
+                version: version.clone(),
+                kind: EventKind::Added,
+                last_seen,
+                temporary: false,
+            });
+        }
+
+        for feature in previous.difference(&current) {
+            if let Some(history) = features.get_mut(feature) {
+                history.last_seen = Some(versions[position - 1].clone());
+                history.events.push(HistoryEvent {
+                    version: version.clone(),
+                    kind: EventKind::Removed,
+                    last_seen: None,
+                    temporary: false,
+                });
+            }
+        }
+
+        previous = current;
+    }
+
+    Ok(HistoryIndex {
+        latest_version: versions.last().cloned(),
+        features,
+    })
+}
+
+impl HistoryIndex {
+    /// The features `version` added back after an earlier build removed them, sorted by name.
+    pub fn reintroduced_in(&self, version: &str) -> Vec<Reintroduction> {
+        let mut reintroduced: Vec<Reintroduction> = Vec::new();
+
+        for (feature, history) in &self.features {
+            for (position, event) in history.events.iter().enumerate() {
+                if event.version != version || event.kind != EventKind::Added {
+                    continue;
+                }
+
+                if let Some(last_seen) = &event.last_seen
+                    && let Some(removal) = position.checked_sub(1).map(|i| &history.events[i])
+                {
+                    reintroduced.push(Reintroduction {
+                        feature: feature.clone(),
+                        last_seen: last_seen.clone(),
+                        removed_in: removal.version.clone(),
+                    });
+                }
+            }
+        }
+
+        reintroduced
+    }
+
+    /// Saves the index as JSON to `file_path`, which belongs in the archive root as HISTORY_FILE.
+    pub fn save(&self, file_path: &Path) -> Result<()> {
+        fs::write(file_path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // An archive with b removed in 1.0.0.2 and back in 1.0.0.3
+    fn archive() -> (tempfile::TempDir, Archive) {
+        let dir: tempfile::TempDir = tempfile::tempdir().unwrap();
+        let archive: Archive = Archive::new(dir.path().to_path_buf());
+
+        for (version, features) in [("1.0.0.1", "a\nb"), ("1.0.0.2", "a"), ("1.0.0.3", "a\nb")] {
+            let version_dir: PathBuf = archive.version_dir(version);
+            fs::create_dir_all(&version_dir).unwrap();
+            fs::write(version_dir.join("original.txt"), features).unwrap();
+        }
+
+        (dir, archive)
+    }
+
+    #[test]
+    fn marks_removals_that_were_undone_as_temporary() {
+        let (_dir, archive) = archive();
+        let index: HistoryIndex = build(&archive).unwrap();
+
+        let history: &FeatureHistory = &index.features["b"];
+        assert_eq!(history.first_seen, "1.0.0.1");
+        assert_eq!(history.last_seen, None);
+        assert!(history.present());
+
+        let events: Vec<(&str, EventKind, Option<&str>, bool)> = history
+            .events
+            .iter()
+            .map(|event| {
+                (
+                    event.version.as_str(),
+                    event.kind,
+                    event.last_seen.as_deref(),
+                    event.temporary,
+                )
+            })
+            .collect();
+        assert_eq!(
+            events,
+            [
+                ("1.0.0.1", EventKind::Added, None, false),
+                ("1.0.0.2", EventKind::Removed, None, true),
+                ("1.0.0.3", EventKind::Added, Some("1.0.0.1"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_features_added_back() {
+        let (_dir, archive) = archive();
+        let index: HistoryIndex = build(&archive).unwrap();
+
+        let reintroduced: Vec<Reintroduction> = index.reintroduced_in("1.0.0.3");
+        assert_eq!(reintroduced.len(), 1);
+        assert_eq!(reintroduced[0].feature, "b");
+        assert_eq!(reintroduced[0].last_seen, "1.0.0.1");
+        assert_eq!(reintroduced[0].removed_in, "1.0.0.2");
+
+        assert!(index.reintroduced_in("1.0.0.2").is_empty());
+    }
+
+    #[test]
+    fn counts_a_staged_version_in() {
+        let (_dir, archive) = archive();
+        let staged: HashSet<String> = HashSet::from(["a".to_string()]);
+        let index: HistoryIndex = build_including(&archive, "1.0.0.4", &staged).unwrap();
+
+        assert_eq!(index.latest_version.as_deref(), Some("1.0.0.4"));
+
+        let history: &FeatureHistory = &index.features["b"];
+        assert!(!history.present());
+        assert_eq!(history.last_seen.as_deref(), Some("1.0.0.3"));
+        assert_eq!(history.events.last().unwrap().kind, EventKind::Removed);
+        assert!(!history.events.last().unwrap().temporary);
+    }
+
+    #[test]
+    fn saves_only_what_changes_with_the_feature() {
+        let (dir, archive) = archive();
+        let path: PathBuf = dir.path().join(HISTORY_FILE);
+        build(&archive).unwrap().save(&path).unwrap();
+        let before: String = fs::read_to_string(&path).unwrap();
+
+        // A build that neither adds nor removes anything leaves the saved index as it was
+        let staged: HashSet<String> = HashSet::from(["a".to_string(), "b".to_string()]);
+        build_including(&archive, "1.0.0.4", &staged)
+            .unwrap()
+            .save(&path)
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+}
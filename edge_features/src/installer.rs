@@ -0,0 +1,133 @@
+use anyhow::{Result, anyhow};
+use sevenz_rust::{Password, SevenZReader};
+use std::io::Cursor;
+
+const SEVEN_ZIP_SIGNATURE: &[u8; 6] = b"7z\xBC\xAF\x27\x1C";
+
+/// The start header before the archive body: signature, version, CRC, next header offset and size.
+const START_HEADER_SIZE: usize = 32;
+
+// msedge.packed.7z holds msedge.7z which holds the browser files, so two levels are expected
+const MAX_ARCHIVE_DEPTH: usize = 3;
+
+/// Finds msedge.dll inside an Edge installer and returns its contents.
+///
+/// The installer carries its payload as 7z archives embedded in its resources, so every
+/// 7z signature in the file is tried and archives found inside archives are opened as well.
+pub fn extract_msedge_dll(installer: &[u8]) -> Result<Vec<u8>> {
+    for archive in embedded_archives(installer) {
+        if let Some(dll) = find_in_archive(archive, 0)? {
+            return Ok(dll);
+        }
+    }
+
+    Err(anyhow!("msedge.dll was not found in the installer payload"))
+}
+
+// Slices out every well-formed 7z archive that starts somewhere inside `data`
+fn embedded_archives(data: &[u8]) -> Vec<&[u8]> {
+    let mut archives: Vec<&[u8]> = Vec::new();
+    let mut offset: usize = 0;
+
+    while let Some(position) = data[offset..]
+        .windows(SEVEN_ZIP_SIGNATURE.len())
+        .position(|window| window == SEVEN_ZIP_SIGNATURE)
+    {
+        let start: usize = offset + position;
+        offset = start + 1;
+
+        // The start header says where the archive's own header ends, which is the end of the archive
+        let Some(header) = data.get(start..start + START_HEADER_SIZE) else {
+            break;
+        };
+
+        // A stray signature in unrelated data will not carry a valid CRC of the rest of the start header
+        let header_crc: u32 = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if crc32(&header[12..START_HEADER_SIZE]) != header_crc {
+            continue;
+        }
+
+        let next_header_offset: u64 = u64::from_le_bytes(header[12..20].try_into().unwrap());
+        let next_header_size: u64 = u64::from_le_bytes(header[20..28].try_into().unwrap());
+
+        let Some(length) = next_header_offset
+            .checked_add(next_header_size)
+            .and_then(|size| usize::try_from(size).ok())
+            .and_then(|size| size.checked_add(START_HEADER_SIZE))
+        else {
+            continue;
+        };
+
+        if let Some(archive) = data.get(start..start.saturating_add(length)) {
+            archives.push(archive);
+            offset = start + length;
+        }
+    }
+
+    archives
+}
+
+fn find_in_archive(archive: &[u8], depth: usize) -> Result<Option<Vec<u8>>> {
+    let Ok(mut reader) = SevenZReader::new(
+        Cursor::new(archive),
+        archive.len() as u64,
+        Password::empty(),
+    ) else {
+        // A signature inside unrelated data, not an actual archive
+        return Ok(None);
+    };
+
+    let mut found: Option<Vec<u8>> = None;
+    let mut nested: Vec<Vec<u8>> = Vec::new();
+
+    reader
+        .for_each_entries(|entry, entry_reader| {
+            let name: String = entry.name().replace('\\', "/").to_ascii_lowercase();
+
+            if name == "msedge.dll" || name.ends_with("/msedge.dll") {
+                println!("Found {} in the installer payload", entry.name());
+                let mut contents: Vec<u8> = Vec::with_capacity(entry.size() as usize);
+                entry_reader.read_to_end(&mut contents)?;
+                found = Some(contents);
+                return Ok(false);
+            }
+
+            if name.ends_with(".7z") && depth + 1 < MAX_ARCHIVE_DEPTH {
+                println!("Unpacking nested archive {}", entry.name());
+                let mut contents: Vec<u8> = Vec::with_capacity(entry.size() as usize);
+                entry_reader.read_to_end(&mut contents)?;
+                nested.push(contents);
+                return Ok(true);
+            }
+
+            // Entries have to be read through to reach the next one in a solid block
+            std::io::copy(entry_reader, &mut std::io::sink())?;
+            Ok(true)
+        })
+        .map_err(|e| anyhow!("Failed to unpack the installer payload: {}", e))?;
+
+    if found.is_some() {
+        return Ok(found);
+    }
+
+    for inner in nested {
+        if let Some(dll) = find_in_archive(&inner, depth + 1)? {
+            return Ok(Some(dll));
+        }
+    }
+
+    Ok(None)
+}
+
+// Plain bitwise CRC-32 (IEEE), only ever used on the 20-byte tail of a start header
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask: u32 = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
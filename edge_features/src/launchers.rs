@@ -0,0 +1,267 @@
+/// What a launcher passes to Edge: features to turn on and off, field trials to force, and the
+/// user data directory to run in.
+#[derive(Clone, Debug, Default)]
+pub struct LaunchSpec {
+    pub enable_features: Vec<String>,
+    pub disable_features: Vec<String>,
+    /// Value of --force-fieldtrials, e.g. "Trial/Group/"
+    pub force_fieldtrials: Option<String>,
+    /// A separate profile directory, so new flags are tried without touching the everyday profile
+    pub user_data_dir: Option<String>,
+}
+
+impl LaunchSpec {
+    pub fn enabling(features: &[String]) -> Self {
+        Self {
+            enable_features: features.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// The command-line switches, with `quote` applied to the values that may contain spaces.
+    pub fn arguments(&self, quote: fn(&str) -> String) -> String {
+        self.switches(quote).join(" ")
+    }
+
+    fn switches(&self, quote: fn(&str) -> String) -> Vec<String> {
+        let mut arguments: Vec<String> = Vec::new();
+
+        if !self.enable_features.is_empty() {
+            arguments.push(format!(
+                "--enable-features={}",
+                self.enable_features.join(",")
+            ));
+        }
+        if !self.disable_features.is_empty() {
+            arguments.push(format!(
+                "--disable-features={}",
+                self.disable_features.join(",")
+            ));
+        }
+        if let Some(trials) = &self.force_fieldtrials {
+            arguments.push(format!("--force-fieldtrials={}", quote(trials)));
+        }
+        if let Some(dir) = &self.user_data_dir {
+            arguments.push(format!("--user-data-dir={}", quote(dir)));
+        }
+
+        arguments
+    }
+
+    /// A browser already running with the same profile would just open a window and ignore the
+    /// switches, so it has to be closed first. A separate profile starts a browser of its own.
+    pub fn closes_running_browser(&self) -> bool {
+        self.user_data_dir.is_none()
+    }
+}
+
+/// Wraps values containing spaces in double quotes, for cmd, shells and desktop entries.
+pub fn double_quote(value: &str) -> String {
+    if value.contains(' ') {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Wraps values containing spaces in single quotes, for the PowerShell shortcut maker.
+pub fn single_quote(value: &str) -> String {
+    if value.contains(' ') {
+        format!("'{}'", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Quotes a whole argument of a desktop entry's Exec key when it has a character the Desktop
+/// Entry spec reserves, escaping for both the quoting and the string value: "--user-data-dir=/a b".
+pub fn desktop_quote(argument: &str) -> String {
+    const RESERVED: &str = " \t\n\"'\\><~|&;$*?#()`";
+
+    let argument: String = argument.replace('%', "%%");
+    if !argument.chars().any(|c| RESERVED.contains(c)) {
+        return argument;
+    }
+
+    let mut quoted: String = String::from("\"");
+    for c in argument.chars() {
+        match c {
+            // Escaped for the quoting, then the backslash itself for the string value
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\\\\\"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A launcher script for one platform, ready to be written next to the PowerShell shortcut maker.
+pub struct Launcher {
+    pub file_name: &'static str,
+    pub description: &'static str,
+    pub content: String,
+}
+
+// Edge Canary only ships for Windows and macOS, Linux gets the Dev channel or else Beta
+const LINUX_BINARIES: [&str; 2] = ["microsoft-edge-dev", "microsoft-edge-beta"];
+
+/// Every launcher that starts Edge as `spec` describes, besides the PowerShell shortcut maker.
+/// `launcher_name` ends up in the window or menu entry titles, e.g. the version the features come from.
+pub fn generate(launcher_name: &str, spec: &LaunchSpec) -> Vec<Launcher> {
+    let arguments: String = spec.arguments(double_quote);
+    let desktop_arguments: String = spec
+        .switches(|value| value.to_string())
+        .iter()
+        .map(|argument| desktop_quote(argument))
+        .collect::<Vec<String>>()
+        .join(" ");
+    let close: bool = spec.closes_running_browser();
+
+    vec![
+        Launcher {
+            file_name: "EdgeCanaryLauncher.cmd",
+            description: "Windows, double-click to run",
+            content: cmd_script(launcher_name, &arguments, close),
+        },
+        Launcher {
+            file_name: "EdgeCanaryLauncher.command",
+            description: "macOS, double-click to run",
+            content: macos_script(launcher_name, &arguments, close),
+        },
+        Launcher {
+            file_name: "EdgeLauncher.sh",
+            description: "Linux, run with bash",
+            content: linux_script(launcher_name, &arguments, close),
+        },
+        Launcher {
+            file_name: "EdgeDevLauncher.desktop",
+            description: "Linux menu entry for Edge Dev, copy to ~/.local/share/applications",
+            content: desktop_entry(launcher_name, "Dev", LINUX_BINARIES[0], &desktop_arguments),
+        },
+        Launcher {
+            file_name: "EdgeBetaLauncher.desktop",
+            description: "Linux menu entry for Edge Beta, copy to ~/.local/share/applications",
+            content: desktop_entry(launcher_name, "Beta", LINUX_BINARIES[1], &desktop_arguments),
+        },
+    ]
+}
+
+fn cmd_script(launcher_name: &str, arguments: &str, close: bool) -> String {
+    let close_line: &str = if close {
+        "powershell.exe -NoProfile -Command \"Get-Process msedge -ErrorAction SilentlyContinue | Where-Object Path -eq '%EDGE%' | Stop-Process -Force\"\r\n"
+    } else {
+        ""
+    };
+
+    format!(
+        "@echo off\r\n\
+        rem EDGECAN Launcher {}\r\n\
+        set \"EDGE=%LOCALAPPDATA%\\Microsoft\\Edge SxS\\Application\\msedge.exe\"\r\n\
+        {}\
+        start \"\" \"%EDGE%\" {}\r\n",
+        launcher_name, close_line, arguments
+    )
+}
+
+fn macos_script(launcher_name: &str, arguments: &str, close: bool) -> String {
+    let close_line: &str = if close {
+        "pkill -x \"Microsoft Edge Canary\" && sleep 2\n"
+    } else {
+        ""
+    };
+
+    format!(
+        "#!/bin/sh\n\
+        # EDGECAN Launcher {}\n\
+        {}\
+        open -na \"Microsoft Edge Canary\" --args {}\n",
+        launcher_name, close_line, arguments
+    )
+}
+
+fn linux_script(launcher_name: &str, arguments: &str, close: bool) -> String {
+    let close_line: &str = if close {
+        "        pkill -f \"/opt/microsoft/msedge-${browser#microsoft-edge-}/msedge\" && sleep 2\n"
+    } else {
+        ""
+    };
+
+    format!(
+        "#!/usr/bin/env bash\n\
+        # Edge Launcher {}\n\
+        for browser in {}; do\n\
+        \x20   if command -v \"$browser\" >/dev/null 2>&1; then\n\
+        {}\
+        \x20       exec \"$browser\" {} \"$@\"\n\
+        \x20   fi\n\
+        done\n\
+        echo \"Neither {} nor {} is installed\" >&2\n\
+        exit 1\n",
+        launcher_name,
+        LINUX_BINARIES.join(" "),
+        close_line,
+        arguments,
+        LINUX_BINARIES[0],
+        LINUX_BINARIES[1]
+    )
+}
+
+// TryExec hides the entry when that channel is not installed
+fn desktop_entry(launcher_name: &str, channel: &str, binary: &str, arguments: &str) -> String {
+    format!(
+        "[Desktop Entry]\n\
+        Type=Application\n\
+        Name=Edge {} Launcher {}\n\
+        TryExec={}\n\
+        Exec={} {} %U\n\
+        Icon={}\n\
+        Terminal=false\n\
+        Categories=Network;WebBrowser;\n",
+        channel, launcher_name, binary, binary, arguments, binary
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_quote_quotes_the_whole_argument() {
+        assert_eq!(
+            desktop_quote("--user-data-dir=/tmp/edge test"),
+            "\"--user-data-dir=/tmp/edge test\""
+        );
+        assert_eq!(
+            desktop_quote("--enable-features=A,B"),
+            "--enable-features=A,B"
+        );
+        assert_eq!(desktop_quote("--x=50%"), "--x=50%%");
+        // Escaped once for the quoting and once more for the string value
+        assert_eq!(
+            desktop_quote(r#"--x=a "b" $c\d"#),
+            r#""--x=a \\"b\\" \\$c\\\\d""#
+        );
+    }
+
+    #[test]
+    fn desktop_entry_exec_line_quotes_the_user_data_dir() {
+        let spec: LaunchSpec = LaunchSpec {
+            user_data_dir: Some("/tmp/edge test".to_string()),
+            ..LaunchSpec::enabling(&["Feature".to_string()])
+        };
+
+        let launchers: Vec<Launcher> = generate("Test", &spec);
+        let desktop: &Launcher = launchers
+            .iter()
+            .find(|launcher| launcher.file_name.ends_with(".desktop"))
+            .unwrap();
+
+        assert!(desktop.content.contains(
+            "Exec=microsoft-edge-dev --enable-features=Feature \"--user-data-dir=/tmp/edge test\" %U"
+        ));
+    }
+}
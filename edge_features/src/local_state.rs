@@ -0,0 +1,142 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Appended to the Local State file name for the copy taken before it is changed.
+const BACKUP_SUFFIX: &str = ".edge_features.bak";
+
+/// Local State of the Edge Canary user data directory of the current user.
+pub fn default_path() -> PathBuf {
+    let username: String = std::env::var("USERNAME").unwrap_or_default();
+    PathBuf::from(format!(
+        "C:\\Users\\{}\\AppData\\Local\\Microsoft\\Edge SxS\\User Data\\Local State",
+        username
+    ))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut file_name: OsString = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(BACKUP_SUFFIX);
+    path.with_file_name(file_name)
+}
+
+/// Whether `name` has the form of an edge://flags entry, lowercase words joined by dashes with an
+/// optional @N option index, which sets it apart from the CamelCase feature names in the archive.
+pub fn is_flag_entry(name: &str) -> bool {
+    let (entry, option): (&str, Option<&str>) = match name.split_once('@') {
+        Some((entry, option)) => (entry, Some(option)),
+        None => (name, None),
+    };
+
+    !entry.is_empty()
+        && entry
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        && option
+            .is_none_or(|option| !option.is_empty() && option.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Adds the flags entries missing from `browser.enabled_labs_experiments`, creating the array if
+/// needed, and returns the ones that were added. Entries already there keep their order.
+pub fn merge(local_state: &mut Value, flags: &[String]) -> Result<Vec<String>> {
+    let browser: &mut Map<String, Value> = local_state
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Local State is not a JSON object"))?
+        .entry("browser")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("browser in Local State is not a JSON object"))?;
+
+    let experiments: &mut Vec<Value> = browser
+        .entry("enabled_labs_experiments")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .ok_or_else(|| {
+            anyhow!("browser.enabled_labs_experiments in Local State is not an array")
+        })?;
+
+    let mut added: Vec<String> = Vec::new();
+    for flag in flags {
+        if !experiments
+            .iter()
+            .any(|entry| entry.as_str() == Some(flag.as_str()))
+        {
+            experiments.push(Value::String(flag.clone()));
+            added.push(flag.clone());
+        }
+    }
+
+    Ok(added)
+}
+
+/// Merges `flags` into the Local State at `path` and returns the ones that were not enabled yet.
+/// The file is backed up first so `undo` can put it back; with `dry_run` nothing is written.
+pub fn enable(path: &Path, flags: &[String], dry_run: bool) -> Result<Vec<String>> {
+    let content: String = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let mut local_state: Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+
+    let added: Vec<String> = merge(&mut local_state, flags)?;
+
+    if dry_run || added.is_empty() {
+        return Ok(added);
+    }
+
+    fs::copy(path, backup_path(path))?;
+    fs::write(path, serde_json::to_string(&local_state)?)?;
+
+    Ok(added)
+}
+
+/// Restores the Local State at `path` from the backup the last `enable` took, and returns the backup's path.
+pub fn undo(path: &Path) -> Result<PathBuf> {
+    let backup: PathBuf = backup_path(path);
+
+    if !backup.is_file() {
+        return Err(anyhow!(
+            "There is no backup of {} to restore",
+            path.display()
+        ));
+    }
+
+    fs::copy(&backup, path)?;
+    fs::remove_file(&backup)?;
+
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_flags_entries_from_feature_names() {
+        assert!(is_flag_entry("edge-show-feature-recommendations"));
+        assert!(is_flag_entry("enable-parallel-downloading@1"));
+        assert!(!is_flag_entry("msEdgeShowFeatureRecommendations"));
+        assert!(!is_flag_entry("flag-name@"));
+        assert!(!is_flag_entry(""));
+    }
+
+    #[test]
+    fn merges_only_missing_flags() {
+        let mut local_state: Value = serde_json::json!({
+            "browser": { "enabled_labs_experiments": ["a-flag"] }
+        });
+
+        let added: Vec<String> = merge(
+            &mut local_state,
+            &["a-flag".to_string(), "b-flag@2".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(added, ["b-flag@2"]);
+        assert_eq!(
+            local_state["browser"]["enabled_labs_experiments"],
+            serde_json::json!(["a-flag", "b-flag@2"])
+        );
+    }
+}
@@ -1,44 +1,146 @@
+mod archive;
+mod case_variants;
+mod categories;
+mod cli;
+mod config;
+mod diff;
+mod families;
+mod feature_state;
+mod history;
+mod installer;
+mod launchers;
+mod local_state;
+mod pe;
+mod publish;
+mod renames;
+mod staging;
+mod strings;
+mod topics;
+mod version_info;
+
 use anyhow::{Result, anyhow};
+use archive::Archive;
+use base64::Engine;
+use case_variants::CaseChange;
+use categories::{CategoryMatcher, StringCategory};
 use chrono::Utc;
-use regex::Regex;
+use clap::Parser;
+use cli::{Cli, Command as CliCommand};
+use config::Config;
+use diff::DiffFormat;
+use families::FamilyGrowth;
+use feature_state::FeatureState;
+use history::Reintroduction;
+use launchers::LaunchSpec;
+use pe::PeFile;
+use publish::{AnyPublisher, Publisher, PublisherKind, Release};
+use renames::Rename;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use staging::Staging;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::Duration;
+use strings::ScanOptions;
 use tokio::time::sleep;
+use version_info::VersionInfo;
 use walkdir::WalkDir;
 
-#[derive(Serialize, Deserialize, Debug)]
-struct GitHubRelease {
-    id: u64,
-    tag_name: String,
-    name: String,
-    body: String,
-    draft: bool,
-    prerelease: bool,
-    target_commitish: String,
+/// Request body for Edge's content delivery API, the same one Edge Update sends.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct TargetingAttributes {
+    is_internal_user: bool,
+    updater: String,
+    updater_version: String,
+    app_lang: String,
+    os_arch: String,
+    os_platform: String,
+    os_region: String,
+    os_version: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentRequest {
+    targeting_attributes: TargetingAttributes,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct ContentId {
+    version: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct ContentSelection {
+    content_id: ContentId,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct FileHashes {
+    sha256: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct DownloadInfo {
+    file_id: String,
+    url: String,
+    hashes: FileHashes,
+}
+
+/// Where a feature name was found inside msedge.dll, and its default state when it could be recovered.
+#[derive(Clone, Debug)]
+struct ExtractedFeature {
+    section: String,
+    rva: u32,
+    default_state: Option<FeatureState>,
+}
+
+/// Names that appeared and disappeared between two builds, each sorted.
+struct ListDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize)]
-struct CreateReleaseRequest {
-    tag_name: String,
-    target_commitish: String,
-    name: String,
-    body: String,
-    draft: bool,
-    prerelease: bool,
+/// The diff of one string category other than features between two builds.
+struct CategoryDiff {
+    category: &'static StringCategory,
+    diff: ListDiff,
 }
 
-#[derive(Serialize, Deserialize)]
-struct UpdateReleaseRequest {
-    body: String,
+/// What processing a build produced, everything publishing a release needs.
+struct BuildReport {
+    full_version: String,
+    added: Vec<String>,
+    added_disabled: Vec<String>,
+    removed: Vec<String>,
+    reintroduced: Vec<Reintroduction>,
+    renamed: Vec<Rename>,
+    case_changed: Vec<CaseChange>,
+    family_growth: Vec<FamilyGrowth>,
+    category_diffs: Vec<CategoryDiff>,
 }
 
+/// Heading in added.txt above the new features that are disabled by default.
+const DISABLED_BY_DEFAULT_HEADING: &str = "# New and disabled by default";
+
+/// Start of the added.txt headings that group re-added features by the version they were last seen in.
+const REINTRODUCED_HEADING: &str = "# Re-added, last seen in";
+
 struct EdgeUpdater {
     client: Client,
+    config: Config,
+    archive: Archive,
+    scan_options: ScanOptions,
+    // Only read-only data sections such as .rdata hold base::Feature names; set to also scan the rest
+    include_all_sections: bool,
 }
 
 /// Compare two dotted-version strings (e.g. "110.0.1587.0") by numeric segments.
@@ -60,166 +162,924 @@ fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     va.len().cmp(&vb.len())
 }
 
+/// Whether `s` looks like an Edge version, four numeric segments such as "153.0.4232.0".
+fn is_dotted_version(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('.').collect();
+    parts.len() == 4
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Compute what was added to and removed from `previous` to get `current`.
+fn diff_sets(current: &HashSet<String>, previous: &HashSet<String>) -> ListDiff {
+    let mut added: Vec<String> = current
+        .iter()
+        .filter(|name| !previous.contains(*name))
+        .cloned()
+        .collect();
+
+    let mut removed: Vec<String> = previous
+        .iter()
+        .filter(|name| !current.contains(*name))
+        .cloned()
+        .collect();
+
+    // Sort the results
+    added.sort();
+    removed.sort();
+
+    ListDiff { added, removed }
+}
+
+/// Render names as a Markdown bullet list, with a single empty bullet when there are none.
+fn markdown_list(items: &[String]) -> String {
+    if items.is_empty() {
+        "* \n".to_string()
+    } else {
+        items.iter().map(|item| format!("* {}\n", item)).collect()
+    }
+}
+
+/// The reintroductions that are among the added features. History compares exact spellings, so a
+/// name whose casing flipped back is reintroduced there but is a case change in `added`'s diff.
+fn reintroduced_among(added: &[String], reintroduced: Vec<Reintroduction>) -> Vec<Reintroduction> {
+    reintroduced
+        .into_iter()
+        .filter(|entry| added.contains(&entry.feature))
+        .collect()
+}
+
+/// Mark the added features that came back after a removal with the version they were last seen in.
+fn label_reintroduced(added: &[String], reintroduced: &[Reintroduction]) -> Vec<String> {
+    added
+        .iter()
+        .map(
+            |feature| match reintroduced.iter().find(|entry| &entry.feature == feature) {
+                Some(entry) => format!("{} (re-added, last seen in {})", feature, entry.last_seen),
+                None => feature.clone(),
+            },
+        )
+        .collect()
+}
+
 impl EdgeUpdater {
-    fn new() -> Self {
+    fn new(config: Config, scan_options: ScanOptions, include_all_sections: bool) -> Self {
         let client: Client = Client::new();
-        Self { client }
+        let archive: Archive = Archive::new(config.data_dir.clone());
+        Self {
+            client,
+            config,
+            archive,
+            scan_options,
+            include_all_sections,
+        }
+    }
+
+    async fn run(&self, install: bool, publisher: &impl Publisher) -> Result<()> {
+        // Get the latest Canary's msedge.dll, either from the offline package or from an actual install
+        let (full_version, data): (String, Vec<u8>) = if install {
+            self.read_installed_edge().await?
+        } else {
+            self.unpack_edge_package().await?
+        };
+
+        let pe: PeFile = PeFile::parse(&data)?;
+
+        // The version we were given is only a hint, the DLL's own version resource has to agree with it
+        match version_info::read(&data, &pe)? {
+            Some(info) => info.verify(&full_version)?,
+            None => {
+                return Err(anyhow!(
+                    "msedge.dll {} has no version resource",
+                    full_version
+                ));
+            }
+        }
+
+        let Some(report) = self.process_build(&data, &pe, &full_version, false)? else {
+            // A run that pushed the build but died before its release left nothing to commit, only
+            // the release to make sure of
+            println!(
+                "BUILD ALREADY EXISTS, publishing the release of {} without committing",
+                full_version
+            );
+            return self.republish(&full_version, publisher).await;
+        };
+
+        publisher.commit_build()?;
+        self.publish_report(&report, publisher).await
     }
 
-    async fn run(&self) -> Result<()> {
+    // Run the pipeline on a local msedge.dll without installing Edge or publishing anything
+    fn run_offline(
+        &self,
+        dll_path: &Path,
+        edge_version: Option<String>,
+        force: bool,
+    ) -> Result<()> {
+        println!("Scanning {} for strings...", dll_path.display());
+        let data: Vec<u8> = fs::read(dll_path)?;
+        let pe: PeFile = PeFile::parse(&data)?;
+
+        let info: Option<VersionInfo> = version_info::read(&data, &pe)?;
+
+        let full_version: String = match (edge_version, &info) {
+            // An explicit version still has to match the resource when the DLL has one
+            (Some(version), Some(info)) => {
+                info.verify(&version)?;
+                version
+            }
+            (Some(version), None) => version,
+            (None, Some(info)) => info
+                .product_version()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "The version resource of {} has no product version",
+                        dll_path.display()
+                    )
+                })?
+                .to_string(),
+            (None, None) => {
+                return Err(anyhow!(
+                    "{} has no version resource, pass the version with --edge-version",
+                    dll_path.display()
+                ));
+            }
+        };
+
+        if !is_dotted_version(&full_version) {
+            return Err(anyhow!("'{}' is not a valid Edge version", full_version));
+        }
+
+        println!("Processing Edge version {} offline", full_version);
+
+        match self.process_build(&data, &pe, &full_version, force)? {
+            Some(report) => println!(
+                "Processed {}: {} features added, {} removed",
+                report.full_version,
+                report.added.len(),
+                report.removed.len()
+            ),
+            None => println!(
+                "BUILD ALREADY EXISTS, pass --force to process {} again.",
+                full_version
+            ),
+        }
+
+        Ok(())
+    }
+
+    // Print or save the features added and removed between two processed versions
+    fn show_diff(
+        &self,
+        from: &str,
+        to: &str,
+        format: DiffFormat,
+        output: Option<&Path>,
+    ) -> Result<()> {
+        let rendered: String = diff::diff_versions(&self.archive, from, to)?.render(format)?;
+
+        match output {
+            Some(path) => {
+                fs::write(path, rendered)?;
+                println!(
+                    "Saved the diff of {} and {} to {}",
+                    from,
+                    to,
+                    path.display()
+                );
+            }
+            None => println!("{}", rendered),
+        }
+
+        Ok(())
+    }
+
+    // Print when a feature first and last appeared, every time it came and went, and whether it is still there
+    fn show_history(&self, feature: &str) -> Result<()> {
+        let index: history::HistoryIndex = history::build(&self.archive)?;
+
+        let Some(entry) = index.features.get(feature) else {
+            println!(
+                "{} is not in any version in {}",
+                feature,
+                self.archive.root.display()
+            );
+
+            // Point at the spellings the archive does have when only the casing is off
+            let key: String = case_variants::canonical_key(feature);
+            for spelling in index
+                .features
+                .keys()
+                .filter(|name| case_variants::canonical_key(name) == key)
+            {
+                println!("Did you mean {}?", spelling);
+            }
+            return Ok(());
+        };
+
+        println!("{}", feature);
+        println!("First seen: {}", entry.first_seen);
+        let latest_version: &str = index.latest_version.as_deref().unwrap_or_default();
         println!(
-            "Current username is: '{}'",
-            std::env::var("USERNAME").unwrap_or_default()
+            "Last seen: {}",
+            entry.last_seen.as_deref().unwrap_or(latest_version)
+        );
+        println!(
+            "Present in the latest version {}: {}",
+            latest_version,
+            if entry.present() { "yes" } else { "no" }
         );
+        for event in &entry.events {
+            match (&event.last_seen, event.temporary) {
+                (Some(last_seen), _) => println!(
+                    "{} {} (re-added, last seen in {})",
+                    event.kind.label(),
+                    event.version,
+                    last_seen
+                ),
+                (None, true) => println!("{} {} (temporary)", event.kind.label(), event.version),
+                (None, false) => println!("{} {}", event.kind.label(), event.version),
+            }
+        }
 
-        // Download Edge Canary installer
-        let edge_installer_path: PathBuf = self.download_edge_canary().await?;
+        Ok(())
+    }
 
-        // Download strings64.exe
-        let strings_exe_path: PathBuf = self.download_strings64().await?;
+    // Print the features of a version that match a regular expression
+    fn search(&self, pattern: &str, version: Option<String>) -> Result<()> {
+        let regex: regex::Regex = regex::Regex::new(pattern)?;
 
-        // Install Edge Canary
-        self.install_edge_canary(&edge_installer_path)?;
+        let version: String = self.archive.version_or_latest(version)?;
 
-        // Wait for installation to complete
-        let app_path: PathBuf = self.get_edge_app_path();
-        self.wait_for_edge_installation(&app_path).await?;
+        let features: Vec<String> = self
+            .archive
+            .read_list(&version, "original.txt")?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|feature| regex.is_match(feature))
+            .collect();
 
-        // Accept Strings64 EULA
-        self.accept_strings_eula()?;
+        for feature in &features {
+            println!("{}", feature);
+        }
+        println!(
+            "{} features in {} match '{}'",
+            features.len(),
+            version,
+            pattern
+        );
 
-        // Find the latest Edge version
-        let full_version: String = self.find_latest_edge_version(&app_path)?;
-        let major_version: String = full_version.split('.').next().unwrap().to_string();
-        let dll_path: PathBuf = app_path.join(&full_version).join("msedge.dll");
+        Ok(())
+    }
 
-        println!("DLL PATH: {}", dll_path.display());
+    // Print the members of a feature family in a version and write the launchers that enable all of them
+    fn show_family(&self, family: &str, version: Option<String>, output_dir: &Path) -> Result<()> {
+        let version: String = self.archive.version_or_latest(version)?;
 
-        // Create directory structure
-        self.create_directory_structure(&major_version)?;
+        let features: Vec<String> = self
+            .archive
+            .read_list(&version, "original.txt")?
+            .unwrap_or_default();
+        let members: Vec<String> = families::members(&features, family);
 
-        // Check if build already exists
-        if self.build_exists(&major_version, &full_version)? {
-            println!("BUILD ALREADY EXISTS, EXITING.");
-            return Ok(());
+        if members.is_empty() {
+            return Err(anyhow!("No features in {} start with {}", version, family));
         }
 
-        // Create new version directory
-        self.create_version_directory(&major_version, &full_version)?;
+        for member in &members {
+            println!("{}", member);
+        }
 
-        // Find previous version for comparison
-        let (previous_full_version, previous_major_version): (String, String) =
-            self.find_previous_version(&major_version)?;
+        println!(
+            "{} members of the {} family in {}",
+            members.len(),
+            family,
+            version
+        );
+
+        self.save_launchers(
+            &format!("{} family {}", family, version),
+            &LaunchSpec::enabling(&members),
+            output_dir,
+        )
+    }
+
+    // Merge edge://flags entries into the enabled experiments of a Local State file
+    fn enable_in_local_state(
+        &self,
+        flags: Vec<String>,
+        local_state_path: Option<PathBuf>,
+        dry_run: bool,
+    ) -> Result<()> {
+        // Edge would drop anything else at startup, feature names are for the launchers
+        if let Some(name) = flags.iter().find(|name| !local_state::is_flag_entry(name)) {
+            return Err(anyhow!(
+                "{} is not an edge://flags entry name, enable features with the launcher command instead",
+                name
+            ));
+        }
+
+        let path: PathBuf = local_state_path.unwrap_or_else(local_state::default_path);
 
+        let added: Vec<String> = local_state::enable(&path, &flags, dry_run)?;
+
+        for flag in &added {
+            println!("+ {}", flag);
+        }
         println!(
-            "Comparing version: {} with version: {}",
-            full_version, previous_full_version
+            "{} of {} flags {} to {}",
+            added.len(),
+            flags.len(),
+            if dry_run { "would be added" } else { "added" },
+            path.display()
         );
 
-        // Extract features using strings64
-        let current_features: HashSet<String> =
-            self.extract_features(&strings_exe_path, &dll_path).await?;
+        Ok(())
+    }
 
-        // Save current features
-        self.save_features(
-            &current_features,
-            &major_version,
-            &full_version,
-            "original.txt",
+    fn undo_enable_in_local_state(&self, local_state_path: Option<PathBuf>) -> Result<()> {
+        let path: PathBuf = local_state_path.unwrap_or_else(local_state::default_path);
+        let backup: PathBuf = local_state::undo(&path)?;
+
+        println!("Restored {} from {}", path.display(), backup.display());
+        Ok(())
+    }
+
+    // Write the shortcut maker and the other launchers for the features added in a version, or in every
+    // version after `since`, plus `extra`, leaving out the ones `extra` disables
+    fn write_launchers(
+        &self,
+        version: Option<String>,
+        since: Option<String>,
+        extra: LaunchSpec,
+        output_dir: &Path,
+    ) -> Result<()> {
+        let version: String = self.archive.version_or_latest(version)?;
+
+        let (launcher_name, mut enable_features): (String, Vec<String>) = match since {
+            Some(since) => {
+                if compare_versions(&since, &version) != std::cmp::Ordering::Less {
+                    return Err(anyhow!("{} is not older than {}", since, version));
+                }
+
+                // Whatever the target has that `since` did not was added in between and not removed again
+                let added: Vec<String> =
+                    diff::diff_versions(&self.archive, &since, &version)?.added;
+                println!(
+                    "{} features were added after {} and are still in {}",
+                    added.len(),
+                    since,
+                    version
+                );
+                (format!("{} to {}", since, version), added)
+            }
+            None => (
+                version.clone(),
+                self.archive
+                    .read_list(&version, "added.txt")?
+                    .unwrap_or_default(),
+            ),
+        };
+        for feature in extra.enable_features {
+            if !enable_features.contains(&feature) {
+                enable_features.push(feature);
+            }
+        }
+
+        // A feature in both lists is disabled, an explicit --disable wins over the added list
+        let conflicting: Vec<String> = enable_features
+            .iter()
+            .filter(|feature| extra.disable_features.contains(feature))
+            .cloned()
+            .collect();
+        enable_features.retain(|feature| !conflicting.contains(feature));
+        for feature in &conflicting {
+            println!("{} is disabled instead of enabled", feature);
+        }
+
+        let spec: LaunchSpec = LaunchSpec {
+            enable_features,
+            ..extra
+        };
+
+        self.save_launchers(&launcher_name, &spec, output_dir)
+    }
+
+    // Write the shortcut maker and the launchers of every other platform into `output_dir`
+    fn save_launchers(
+        &self,
+        launcher_name: &str,
+        spec: &LaunchSpec,
+        output_dir: &Path,
+    ) -> Result<()> {
+        fs::create_dir_all(output_dir)?;
+        self.create_shortcut_maker(
+            launcher_name,
+            spec,
+            &output_dir.join("EdgeCanaryShortcutMaker.ps1"),
         )?;
+        self.create_launchers(launcher_name, spec, output_dir)?;
 
-        // Load previous features
-        let previous_features: HashSet<String> =
-            self.load_previous_features(&previous_major_version, &previous_full_version)?;
+        println!(
+            "Launchers for {} with {} features enabled and {} disabled saved to {}",
+            launcher_name,
+            spec.enable_features.len(),
+            spec.disable_features.len(),
+            output_dir.display()
+        );
+
+        Ok(())
+    }
+
+    // Regenerate the README block from the last processed version's files
+    fn rebuild_readme(&self) -> Result<()> {
+        let full_version: String = fs::read_to_string("last.txt")?.trim().to_string();
+        self.archive.require_version(&full_version)?;
+
+        let added: Vec<String> = self
+            .archive
+            .read_list(&full_version, "added.txt")?
+            .unwrap_or_default();
+
+        let reintroduced: Vec<Reintroduction> = reintroduced_among(
+            &added,
+            history::build(&self.archive)?.reintroduced_in(&full_version),
+        );
+
+        self.update_readme(&full_version, &added, &reintroduced, Path::new("README.md"))?;
+        println!(
+            "README rebuilt for {} ({} added features)",
+            full_version,
+            added.len()
+        );
+
+        Ok(())
+    }
+
+    // Publishes a processed version again, with its launchers regenerated from added.txt
+    async fn republish(&self, full_version: &str, publisher: &impl Publisher) -> Result<()> {
+        self.archive.require_version(full_version)?;
 
-        // Calculate differences
-        let mut added: Vec<String> = current_features
+        let report: BuildReport = self.load_report(full_version)?;
+        self.publish_report(&report, publisher).await
+    }
+
+    // Publishes the release of a build with launchers written for it alone, since the ones in the
+    // working directory belong to the last processed version, which may be a newer one
+    async fn publish_report(&self, report: &BuildReport, publisher: &impl Publisher) -> Result<()> {
+        let full_version: &str = &report.full_version;
+
+        let assets_dir: tempfile::TempDir = tempfile::tempdir()?;
+        let spec: LaunchSpec = LaunchSpec::enabling(&report.added);
+        self.create_shortcut_maker(
+            full_version,
+            &spec,
+            &assets_dir.path().join("EdgeCanaryShortcutMaker.ps1"),
+        )?;
+        self.create_launchers(full_version, &spec, assets_dir.path())?;
+
+        publisher
+            .publish(&self.build_release(report, assets_dir.path()))
+            .await
+    }
+
+    // The report process_build gave for a version, rebuilt from the lists it saved
+    fn load_report(&self, full_version: &str) -> Result<BuildReport> {
+        let major_version: &str = full_version.split('.').next().unwrap();
+        let previous: Option<(String, String)> =
+            self.find_previous_version(major_version, full_version)?;
+
+        let current_features: HashSet<String> = self
+            .archive
+            .read_list(full_version, "original.txt")?
+            .ok_or_else(|| anyhow!("{} has no original.txt", full_version))?
+            .into_iter()
+            .collect();
+        let previous_features: HashSet<String> = self.load_previous_features(previous.as_ref())?;
+
+        let (ListDiff { added, removed }, case_changed): (ListDiff, Vec<CaseChange>) =
+            case_variants::diff(&current_features, &previous_features);
+
+        // The same filter process_build applied, re-added features that are disabled included
+        let disabled: HashSet<String> = self.load_disabled_features(full_version)?;
+        let added_disabled: Vec<String> = added
             .iter()
-            .filter(|feature| !previous_features.contains(*feature))
+            .filter(|feature| disabled.contains(*feature))
             .cloned()
             .collect();
 
-        let mut removed: Vec<String> = previous_features
+        let category_diffs: Vec<CategoryDiff> = categories::CATEGORIES
             .iter()
-            .filter(|feature| !current_features.contains(*feature))
+            .copied()
+            .filter(|category| category.name != categories::FEATURES.name)
+            .map(|category| -> Result<CategoryDiff> {
+                Ok(CategoryDiff {
+                    category,
+                    diff: ListDiff {
+                        added: self
+                            .archive
+                            .read_list(full_version, category.added_file)?
+                            .unwrap_or_default(),
+                        removed: self
+                            .archive
+                            .read_list(full_version, category.removed_file)?
+                            .unwrap_or_default(),
+                    },
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(BuildReport {
+            full_version: full_version.to_string(),
+            reintroduced: reintroduced_among(
+                &added,
+                history::build(&self.archive)?.reintroduced_in(full_version),
+            ),
+            renamed: renames::detect(&removed, &added),
+            family_growth: families::growth(&current_features, &added),
+            added,
+            added_disabled,
+            removed,
+            case_changed,
+            category_diffs,
+        })
+    }
+
+    // Extract, save and diff everything in a build, then refresh the files that describe the latest build.
+    // Returns None when the build was already processed and `force` is not set
+    fn process_build(
+        &self,
+        data: &[u8],
+        pe: &PeFile,
+        full_version: &str,
+        force: bool,
+    ) -> Result<Option<BuildReport>> {
+        let major_version: String = full_version.split('.').next().unwrap().to_string();
+
+        // Check if build already exists
+        if self.build_exists(full_version) && !force {
+            return Ok(None);
+        }
+
+        // Every output goes to a staging directory first and is only moved into place once all
+        // of it was written, so a failure midway leaves nothing half-done behind
+        let mut staging: Staging = Staging::new(&[Path::new("."), &self.archive.root])?;
+        let staged_dir: PathBuf = staging.path_for(&self.archive.version_dir(full_version));
+        fs::create_dir_all(&staged_dir)?;
+
+        // Find previous version for comparison
+        let previous: Option<(String, String)> =
+            self.find_previous_version(&major_version, full_version)?;
+
+        match &previous {
+            Some((previous_full_version, _)) => println!(
+                "Comparing version: {} with version: {}",
+                full_version, previous_full_version
+            ),
+            None => println!(
+                "No version before {} in {}, every feature counts as added",
+                full_version,
+                self.archive.root.display()
+            ),
+        }
+
+        // Extract every string category straight from the DLL in one pass
+        let mut found: HashMap<&'static str, BTreeMap<String, ExtractedFeature>> =
+            self.extract_categories(data, pe)?;
+
+        let mut extracted: BTreeMap<String, ExtractedFeature> =
+            found.remove(categories::FEATURES.name).unwrap_or_default();
+        self.resolve_feature_states(data, pe, &mut extracted);
+
+        let current_features: HashSet<String> = extracted.keys().cloned().collect();
+
+        // Save current features
+        self.save_features(&current_features, &staged_dir, "original.txt")?;
+
+        // Save where each feature was found
+        self.save_feature_locations(&extracted, &staged_dir)?;
+
+        // Keep the per-feature history index in step with the archive, counting this build in
+        let history_index: history::HistoryIndex =
+            history::build_including(&self.archive, full_version, &current_features)?;
+        let history_path: PathBuf = self.archive.root.join(history::HISTORY_FILE);
+        history_index.save(&staging.path_for(&history_path))?;
+        println!("Feature history saved to {}", history_path.display());
+
+        // Load previous features
+        let previous_features: HashSet<String> = self.load_previous_features(previous.as_ref())?;
+
+        // Calculate differences, keeping names that only changed case out of added and removed
+        let (ListDiff { added, removed }, case_changed): (ListDiff, Vec<CaseChange>) =
+            case_variants::diff(&current_features, &previous_features);
+
+        // New features that ship turned off need to be enabled explicitly to be tried out
+        let added_disabled: Vec<String> = added
+            .iter()
+            .filter(|feature| {
+                extracted.get(*feature).and_then(|f| f.default_state)
+                    == Some(FeatureState::DisabledByDefault)
+            })
             .cloned()
             .collect();
 
-        // Sort the results
-        added.sort();
-        removed.sort();
+        // Features that come back after a removal are told apart from brand-new ones
+        let reintroduced: Vec<Reintroduction> =
+            reintroduced_among(&added, history_index.reintroduced_in(full_version));
 
         // Save differences
-        self.save_feature_list(&added, &major_version, &full_version, "added.txt")?;
-        self.save_feature_list(&removed, &major_version, &full_version, "removed.txt")?;
+        self.save_added_features(&added, &added_disabled, &reintroduced, &staged_dir)?;
+        self.save_feature_list(&removed, &staged_dir, "removed.txt")?;
+
+        // A removal and an addition that are the same feature under a new name
+        let renamed: Vec<Rename> = renames::detect(&removed, &added);
+        let renamed_labels: Vec<String> = renamed.iter().map(Rename::label).collect();
+        self.save_feature_list(&renamed_labels, &staged_dir, "renamed.txt")?;
+
+        let case_changed_labels: Vec<String> = case_changed.iter().map(CaseChange::label).collect();
+        self.save_feature_list(&case_changed_labels, &staged_dir, "case_changed.txt")?;
+
+        // Every spelling of a name that occurs in more than one casing, under its canonical key
+        self.save_feature_list(
+            &case_variants::alias_lines(&current_features),
+            &staged_dir,
+            "aliases.txt",
+        )?;
+
+        // Families of related flags that this build gave new members
+        let family_growth: Vec<FamilyGrowth> = families::growth(&current_features, &added);
+        let family_labels: Vec<String> = family_growth.iter().map(FamilyGrowth::label).collect();
+        self.save_feature_list(&family_labels, &staged_dir, "families.txt")?;
 
+        let version_dir: PathBuf = self.archive.version_dir(full_version);
         println!(
-            "Added features ./Edge Canary/{}/{}/added.txt ({} entries)",
-            major_version,
-            full_version,
+            "Added features {} ({} entries)",
+            version_dir.join("added.txt").display(),
             added.len()
         );
         println!(
-            "Removed features ./Edge Canary/{}/{}/removed.txt ({} entries)",
-            major_version,
-            full_version,
+            "Removed features {} ({} entries)",
+            version_dir.join("removed.txt").display(),
             removed.len()
         );
+        println!(
+            "Renamed features {} ({} entries)",
+            version_dir.join("renamed.txt").display(),
+            renamed.len()
+        );
+        println!(
+            "Features that only changed case {} ({} entries)",
+            version_dir.join("case_changed.txt").display(),
+            case_changed.len()
+        );
+        println!(
+            "Feature families that gained members {} ({} entries)",
+            version_dir.join("families.txt").display(),
+            family_growth.len()
+        );
 
-        // Update last.txt
-        fs::write("last.txt", &full_version)?;
+        // Save and diff the other string categories the same way
+        let mut category_diffs: Vec<CategoryDiff> = Vec::new();
+        for category in categories::CATEGORIES.iter().copied() {
+            if category.name == categories::FEATURES.name {
+                continue;
+            }
 
-        // Update README
-        self.update_readme(&full_version, &added)?;
+            let current: HashSet<String> = found
+                .remove(category.name)
+                .unwrap_or_default()
+                .into_keys()
+                .collect();
+            self.save_features(&current, &staged_dir, category.list_file)?;
+
+            let diff: ListDiff = match &previous {
+                // The first version of the archive adds everything, like it does for features
+                None => diff_sets(&current, &HashSet::new()),
+                Some((previous_full_version, _)) => match self
+                    .archive
+                    .read_list(previous_full_version, category.list_file)?
+                {
+                    Some(previous) => diff_sets(&current, &previous.into_iter().collect()),
+                    None => {
+                        // Builds processed before this category was tracked have nothing to compare against
+                        println!(
+                            "No {} for {}, skipping the {} diff",
+                            category.list_file, previous_full_version, category.name
+                        );
+                        ListDiff {
+                            added: Vec::new(),
+                            removed: Vec::new(),
+                        }
+                    }
+                },
+            };
+
+            self.save_feature_list(&diff.added, &staged_dir, category.added_file)?;
+            self.save_feature_list(&diff.removed, &staged_dir, category.removed_file)?;
+
+            println!(
+                "{}: {} added, {} removed",
+                category.title,
+                diff.added.len(),
+                diff.removed.len()
+            );
+
+            category_diffs.push(CategoryDiff { category, diff });
+        }
 
-        // Create Edge Canary shortcut maker
-        self.create_shortcut_maker(&full_version, &added)?;
+        // Reprocessing an older build must not roll back the files that describe the latest one
+        let last_version: String = fs::read_to_string("last.txt").unwrap_or_default();
+        if compare_versions(full_version, last_version.trim()) == std::cmp::Ordering::Less {
+            println!(
+                "{} is older than the last processed version {}, leaving last.txt, README and the shortcut maker alone",
+                full_version,
+                last_version.trim()
+            );
+        } else {
+            // Update last.txt
+            fs::write(staging.path_for(Path::new("last.txt")), full_version)?;
 
-        // Commit and push changes
-        self.commit_and_push()?;
+            // Update README
+            self.update_readme(
+                full_version,
+                &added,
+                &reintroduced,
+                &staging.path_for(Path::new("README.md")),
+            )?;
+
+            // Create Edge Canary shortcut maker
+            let spec: LaunchSpec = LaunchSpec::enabling(&added);
+            self.create_shortcut_maker(
+                full_version,
+                &spec,
+                &staging.path_for(Path::new("EdgeCanaryShortcutMaker.ps1")),
+            )?;
 
-        // Create GitHub release
-        self.create_github_release(&full_version, &added, &removed)
-            .await?;
+            // And the same launcher for the other platforms
+            self.create_launchers(full_version, &spec, &staging.dir_for_files(Path::new("."))?)?;
 
-        Ok(())
+            // Regenerate the feature list and shortcut maker of every topic
+            self.update_topic_shortcuts(
+                full_version,
+                &current_features,
+                &history_index,
+                &mut staging,
+            )?;
+        }
+
+        self.validate_staged_build(&staged_dir, &current_features)?;
+
+        let moved: usize = staging.commit()?;
+        self.archive.mark_complete(full_version)?;
+        println!(
+            "Moved {} outputs into place, {} is complete",
+            moved, full_version
+        );
+
+        Ok(Some(BuildReport {
+            full_version: full_version.to_string(),
+            added,
+            added_disabled,
+            removed,
+            reintroduced,
+            renamed,
+            case_changed,
+            family_growth,
+            category_diffs,
+        }))
     }
 
-    async fn download_edge_canary(&self) -> Result<PathBuf> {
-        let url1: &str =
-            "https://go.microsoft.com/fwlink/?linkid=2084706&Channel=Canary&language=en";
-        let url2: &str = "https://c2rsetup.edog.officeapps.live.com/c2r/downloadEdge.aspx?platform=Default&source=EdgeInsiderPage&Channel=Canary&language=en";
+    // Download the offline installer package of the latest Canary and take msedge.dll out of it in-process
+    async fn unpack_edge_package(&self) -> Result<(String, Vec<u8>)> {
+        let (full_version, package_path): (String, PathBuf) = self.download_edge_package().await?;
 
-        let temp_dir: PathBuf = std::env::temp_dir();
-        let installer_path: PathBuf = temp_dir.join("MicrosoftEdgeSetupCanary.exe");
+        println!("Unpacking msedge.dll from {}", package_path.display());
+        let package: Vec<u8> = fs::read(&package_path)?;
+        let data: Vec<u8> = installer::extract_msedge_dll(&package)?;
 
-        println!("Downloading Edge Canary");
+        Ok((full_version, data))
+    }
+
+    async fn download_edge_package(&self) -> Result<(String, PathBuf)> {
+        let api: &str = &self.config.downloads.package_api;
+
+        let request: ContentRequest = ContentRequest {
+            targeting_attributes: TargetingAttributes {
+                is_internal_user: true,
+                updater: "MicrosoftEdgeUpdate".to_string(),
+                updater_version: "1.3.195.61".to_string(),
+                app_lang: "en-US".to_string(),
+                os_arch: "x64".to_string(),
+                os_platform: "win".to_string(),
+                os_region: "US".to_string(),
+                os_version: "10.0.26100".to_string(),
+            },
+        };
 
-        // Try primary URL first
-        println!("Trying the primary URL");
-        let result: Result<(), anyhow::Error> = self.download_file(url1, &installer_path).await;
+        println!("Looking up the latest Edge Canary version");
+        let selection: ContentSelection = self
+            .client
+            .post(format!("{}/latest?action=select", api))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let full_version: String = selection.content_id.version;
 
-        if result.is_err() {
-            println!("Downloading from the primary URL failed, trying the secondary URL");
-            self.download_file(url2, &installer_path)
-                .await
-                .map_err(|_| anyhow!("Failed to download Edge from both URLs"))?;
+        println!("Latest Edge Canary version is {}", full_version);
+
+        let files: Vec<DownloadInfo> = self
+            .client
+            .post(format!(
+                "{}/{}/files?action=GenerateDownloadInfo",
+                api, full_version
+            ))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // The full package is the one without a second version, the rest are deltas from older builds
+        let package_name: String = format!("MicrosoftEdge_X64_{}.exe", full_version);
+        let package: DownloadInfo = files
+            .into_iter()
+            .find(|file| file.file_id.eq_ignore_ascii_case(&package_name))
+            .ok_or_else(|| anyhow!("No full installer package offered for {}", full_version))?;
+
+        let package_path: PathBuf = std::env::temp_dir().join(&package.file_id);
+
+        println!("Downloading {}", package.file_id);
+        self.download_file(&package.url, &package_path).await?;
+
+        // Make sure the package is exactly what the API described
+        let digest: String = base64::engine::general_purpose::STANDARD
+            .encode(Sha256::digest(fs::read(&package_path)?));
+        if digest != package.hashes.sha256 {
+            return Err(anyhow!(
+                "SHA-256 mismatch for {}: expected {}, got {}",
+                package.file_id,
+                package.hashes.sha256,
+                digest
+            ));
         }
 
-        Ok(installer_path)
+        Ok((full_version, package_path))
     }
 
-    async fn download_strings64(&self) -> Result<PathBuf> {
-        let url: &str = "https://live.sysinternals.com/strings64.exe";
+    // Run the Canary setup, wait for it to finish and read msedge.dll from the install location
+    async fn read_installed_edge(&self) -> Result<(String, Vec<u8>)> {
+        println!(
+            "Current username is: '{}'",
+            std::env::var("USERNAME").unwrap_or_default()
+        );
+
+        // Download Edge Canary installer
+        let edge_installer_path: PathBuf = self.download_edge_canary().await?;
+
+        // Install Edge Canary
+        self.install_edge_canary(&edge_installer_path)?;
+
+        // Wait for installation to complete
+        let app_path: PathBuf = self.get_edge_app_path();
+        self.wait_for_edge_installation(&app_path).await?;
+
+        // Find the latest Edge version
+        let full_version: String = self.find_latest_edge_version(&app_path)?;
+        let dll_path: PathBuf = app_path.join(&full_version).join("msedge.dll");
+
+        println!("DLL PATH: {}", dll_path.display());
+
+        let data: Vec<u8> = fs::read(&dll_path)?;
+        Ok((full_version, data))
+    }
+
+    async fn download_edge_canary(&self) -> Result<PathBuf> {
+        let urls: &[String] = &self.config.downloads.installer_urls;
+
         let temp_dir: PathBuf = std::env::temp_dir();
-        let strings_path: PathBuf = temp_dir.join("strings64.exe");
+        let installer_path: PathBuf = temp_dir.join("MicrosoftEdgeSetupCanary.exe");
 
-        println!("Downloading Strings64.exe");
-        self.download_file(url, &strings_path)
-            .await
-            .map_err(|_| anyhow!("Failed to download Strings64"))?;
+        println!("Downloading Edge Canary");
+
+        // Try the URLs in order until one works
+        for url in urls {
+            println!("Trying {}", url);
+            match self.download_file(url, &installer_path).await {
+                Ok(()) => return Ok(installer_path),
+                Err(e) => println!("Downloading from {} failed: {}", url, e),
+            }
+        }
 
-        Ok(strings_path)
+        Err(anyhow!(
+            "Failed to download Edge from all {} URLs",
+            urls.len()
+        ))
     }
 
     async fn download_file(&self, url: &str, path: &Path) -> Result<()> {
@@ -258,10 +1118,10 @@ impl EdgeUpdater {
             }
 
             // Look for msedge.dll recursively
-            if let Some(_) = WalkDir::new(app_path)
+            if WalkDir::new(app_path)
                 .into_iter()
                 .filter_map(|e| e.ok())
-                .find(|entry| entry.file_name() == "msedge.dll")
+                .any(|entry| entry.file_name() == "msedge.dll")
             {
                 println!("File found: Edge Canary installation completed");
                 return Ok(());
@@ -272,38 +1132,17 @@ impl EdgeUpdater {
         }
     }
 
-    fn accept_strings_eula(&self) -> Result<()> {
-        println!("Accepting Strings64's EULA via Registry");
-
-        #[cfg(windows)]
-        {
-            use winreg::RegKey;
-            use winreg::enums::*;
-
-            let hkcu: RegKey = RegKey::predef(HKEY_CURRENT_USER);
-            let path: &str = "Software\\Sysinternals\\Strings";
-
-            let key: RegKey = hkcu
-                .create_subkey(path)
-                .map_err(|e| anyhow!("Failed to create registry key: {}", e))?
-                .0;
-
-            key.set_value("EulaAccepted", &1u32)
-                .map_err(|e| anyhow!("Failed to set registry value: {}", e))?;
-        }
-
-        Ok(())
-    }
-
     fn find_latest_edge_version(&self, app_path: &Path) -> Result<String> {
         println!("Searching for the Edge Canary version that was just downloaded");
 
-        // Collect all subdirs whose name starts with '1'
+        // Collect all subdirs named like a version that actually hold an msedge.dll,
+        // leftovers of uninstalled builds are usually emptied out
         let mut versions: Vec<String> = fs::read_dir(app_path)?
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+            .filter(|e| e.path().join("msedge.dll").is_file())
             .map(|e| e.file_name().to_string_lossy().into_owned())
-            .filter(|name| name.starts_with('1'))
+            .filter(|name| is_dotted_version(name))
             .collect();
 
         if versions.is_empty() {
@@ -315,62 +1154,75 @@ impl EdgeUpdater {
         Ok(versions.pop().unwrap())
     }
 
-    fn create_directory_structure(&self, major_version: &str) -> Result<()> {
-        let edge_canary_dir: PathBuf = PathBuf::from("Edge Canary");
-        if !edge_canary_dir.exists() {
-            fs::create_dir_all(&edge_canary_dir)?;
-        }
-
-        let major_version_dir: PathBuf = edge_canary_dir.join(major_version);
-        if !major_version_dir.exists() {
-            fs::create_dir_all(&major_version_dir)?;
-        }
-
-        Ok(())
+    // A version directory that is not complete is what a failed run left behind, and is processed again
+    fn build_exists(&self, full_version: &str) -> bool {
+        self.archive.is_complete(full_version)
     }
 
-    fn build_exists(&self, major_version: &str, full_version: &str) -> Result<bool> {
-        let version_dir: PathBuf = PathBuf::from("Edge Canary")
-            .join(major_version)
-            .join(full_version);
-
-        if !version_dir.exists() {
-            return Ok(false);
+    // Catches a run that found nothing or skipped a list before it is recorded as complete
+    fn validate_staged_build(&self, dir: &Path, features: &HashSet<String>) -> Result<()> {
+        if features.is_empty() {
+            return Err(anyhow!("No features were found in msedge.dll"));
         }
 
-        // Check if directory has any files
-        let has_files: bool = fs::read_dir(&version_dir)?.next().is_some();
+        let mut expected: Vec<&str> = vec![
+            "locations.txt",
+            "renamed.txt",
+            "case_changed.txt",
+            "aliases.txt",
+            "families.txt",
+        ];
+        for category in categories::CATEGORIES.iter() {
+            expected.extend([
+                category.list_file,
+                category.added_file,
+                category.removed_file,
+            ]);
+        }
 
-        Ok(has_files)
-    }
+        if let Some(missing) = expected.iter().find(|file| !dir.join(file).is_file()) {
+            return Err(anyhow!("{} was not written", missing));
+        }
 
-    fn create_version_directory(&self, major_version: &str, full_version: &str) -> Result<()> {
-        let version_dir: PathBuf = PathBuf::from("Edge Canary")
-            .join(major_version)
-            .join(full_version);
+        let saved: usize = fs::read_to_string(dir.join("original.txt"))?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .count();
+        if saved != features.len() {
+            return Err(anyhow!(
+                "original.txt has {} features instead of {}",
+                saved,
+                features.len()
+            ));
+        }
 
-        fs::create_dir_all(&version_dir)?;
         Ok(())
     }
 
-    fn find_previous_version(&self, current_major_version: &str) -> Result<(String, String)> {
-        let base_dir = PathBuf::from("Edge Canary");
+    // The newest version older than the current one with its major, or None when the archive has
+    // nothing older, e.g. when it is just being started
+    fn find_previous_version(
+        &self,
+        current_major_version: &str,
+        current_full_version: &str,
+    ) -> Result<Option<(String, String)>> {
+        let base_dir = self.archive.root.clone();
         let current_dir = base_dir.join(current_major_version);
 
-        // 1) Collect all full-version folders in this major
-        let mut versions: Vec<String> = fs::read_dir(&current_dir)?
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-            .map(|e| e.file_name().to_string_lossy().into_owned())
+        if !base_dir.exists() {
+            return Ok(None);
+        }
+
+        // 1) Collect all full-version folders in this major that are older than the current one
+        let versions: Vec<String> = self
+            .get_versions_in_directory(&current_dir)?
+            .into_iter()
+            .filter(|name| compare_versions(name, current_full_version) == std::cmp::Ordering::Less)
             .collect();
 
-        // If there are at least two, pick the second-to-last as "previous"
-        if versions.len() >= 2 {
-            // Sort ascending by numeric segments
-            versions.sort_by(|a, b| compare_versions(a, b));
-            // previous is the one right before the last
-            let previous_full = versions[versions.len() - 2].clone();
-            return Ok((previous_full, current_major_version.to_string()));
+        // The newest of those is "previous", which also holds when an older build is reprocessed
+        if let Some(previous_full) = versions.into_iter().max_by(|a, b| compare_versions(a, b)) {
+            return Ok(Some((previous_full, current_major_version.to_string())));
         }
 
         // 2) Otherwise, find the last version in the previous major
@@ -390,13 +1242,11 @@ impl EdgeUpdater {
             })
             .collect();
 
-        // Sort descending and skip the current major
+        // Sort descending and only look at majors older than the current one
+        let current_major: u32 = current_major_version.parse().unwrap_or(u32::MAX);
         majors.sort_unstable();
         majors.reverse();
-        for &maj in majors
-            .iter()
-            .skip_while(|&&m| m.to_string() == current_major_version)
-        {
+        for &maj in majors.iter().filter(|&&m| m < current_major) {
             let mut other_versions =
                 self.get_versions_in_directory(&base_dir.join(maj.to_string()))?;
             if other_versions.is_empty() {
@@ -405,10 +1255,10 @@ impl EdgeUpdater {
             other_versions.sort_by(|a, b| compare_versions(a, b));
             // newest from that major
             let prev_full = other_versions.pop().unwrap();
-            return Ok((prev_full, maj.to_string()));
+            return Ok(Some((prev_full, maj.to_string())));
         }
 
-        Err(anyhow!("No previous version found"))
+        Ok(None)
     }
 
     fn get_versions_in_directory(&self, dir: &Path) -> Result<Vec<String>> {
@@ -426,45 +1276,86 @@ impl EdgeUpdater {
         Ok(versions)
     }
 
-    // Extract features: lines that begin with "ms" (case-Insensitive) followed by at least 4 more alphanumeric characters
-    async fn extract_features(
+    // Match every printable string in the scanned sections against each category, keeping the first location of each name
+    fn extract_categories(
         &self,
-        strings_exe: &Path,
-        dll_path: &Path,
-    ) -> Result<HashSet<String>> {
-        println!("Strings64 Running...");
-
-        let output = Command::new(strings_exe).arg(dll_path).output()?;
-        let stdout: String = String::from_utf8_lossy(&output.stdout).to_string();
+        data: &[u8],
+        pe: &PeFile,
+    ) -> Result<HashMap<&'static str, BTreeMap<String, ExtractedFeature>>> {
+        let matchers: Vec<CategoryMatcher> = categories::CATEGORIES
+            .iter()
+            .map(|category| category.matcher())
+            .collect::<Result<Vec<CategoryMatcher>>>()?;
 
-        // Pattern: lines that start with "ms" (case-Insensitive) followed by at least 4 alphanumeric characters
-        let regex: Regex = Regex::new(r"(?i)^ms[a-zA-Z0-9]{4,}$")?;
+        let mut found: HashMap<&'static str, BTreeMap<String, ExtractedFeature>> = HashMap::new();
 
-        let mut features: HashSet<String> = HashSet::new();
+        for section in &pe.sections {
+            if !self.include_all_sections && !section.is_read_only_data() {
+                continue;
+            }
 
-        // Process each line from strings64.exe output
-        for line in stdout.lines() {
-            // Check if line begins with "ms" (case-Insensitive) and has at least 4 more alphanumeric characters
-            if regex.is_match(line) {
-                features.insert(line.to_string());
+            let range: std::ops::Range<usize> = section.raw_range(data.len());
+
+            // Process each ASCII and UTF-16LE run found in the section
+            for run in strings::scan(&data[range], &self.scan_options) {
+                let rva: u32 = section.virtual_address + (run.offset as u32);
+
+                for matcher in &matchers {
+                    let Some(name) = matcher.match_text(&run.text) else {
+                        continue;
+                    };
+
+                    // Keep the first location if a name occurs more than once
+                    found
+                        .entry(matcher.category.name)
+                        .or_default()
+                        .entry(name)
+                        .or_insert_with(|| ExtractedFeature {
+                            section: section.name.clone(),
+                            rva,
+                            default_state: None,
+                        });
+                }
             }
         }
 
-        println!("Extracted {} features", features.len());
-        Ok(features)
+        for matcher in &matchers {
+            println!(
+                "Extracted {} {}",
+                found.get(matcher.category.name).map_or(0, |f| f.len()),
+                matcher.category.name
+            );
+        }
+
+        Ok(found)
     }
 
-    fn save_features(
+    // Read the default state from the base::Feature object that points at each feature name
+    fn resolve_feature_states(
         &self,
-        features: &HashSet<String>,
-        major_version: &str,
-        full_version: &str,
-        filename: &str,
-    ) -> Result<()> {
-        let file_path: PathBuf = PathBuf::from("Edge Canary")
-            .join(major_version)
-            .join(full_version)
-            .join(filename);
+        data: &[u8],
+        pe: &PeFile,
+        features: &mut BTreeMap<String, ExtractedFeature>,
+    ) {
+        let names_by_rva: HashMap<u32, String> = features
+            .iter()
+            .map(|(name, feature)| (feature.rva, name.clone()))
+            .collect();
+
+        let states: HashMap<String, FeatureState> =
+            feature_state::resolve_default_states(data, pe, &names_by_rva);
+
+        for (name, state) in &states {
+            if let Some(feature) = features.get_mut(name) {
+                feature.default_state = Some(*state);
+            }
+        }
+
+        println!("Recovered the default state of {} features", states.len());
+    }
+
+    fn save_features(&self, features: &HashSet<String>, dir: &Path, filename: &str) -> Result<()> {
+        let file_path: PathBuf = dir.join(filename);
 
         let mut sorted_features: Vec<&String> = features.iter().collect();
         sorted_features.sort();
@@ -477,25 +1368,12 @@ impl EdgeUpdater {
 
         fs::write(&file_path, content)?;
 
-        println!(
-            "Saved: {} ({} entries)",
-            file_path.display(),
-            features.len()
-        );
+        println!("Saved: {} ({} entries)", filename, features.len());
         Ok(())
     }
 
-    fn save_feature_list(
-        &self,
-        features: &[String],
-        major_version: &str,
-        full_version: &str,
-        filename: &str,
-    ) -> Result<()> {
-        let file_path: PathBuf = PathBuf::from("Edge Canary")
-            .join(major_version)
-            .join(full_version)
-            .join(filename);
+    fn save_feature_list(&self, features: &[String], dir: &Path, filename: &str) -> Result<()> {
+        let file_path: PathBuf = dir.join(filename);
 
         // Don't sort here since we already sorted in the calling function
         let content: String = features.join("\n");
@@ -504,13 +1382,114 @@ impl EdgeUpdater {
         Ok(())
     }
 
-    // Load previous features - process lines the same way as extract_features
+    // Brand-new features that are not known to be disabled come first, then the disabled-by-default ones under a heading,
+    // then the re-added ones under one heading per version they were last seen in
+    fn save_added_features(
+        &self,
+        added: &[String],
+        added_disabled: &[String],
+        reintroduced: &[Reintroduction],
+        dir: &Path,
+    ) -> Result<()> {
+        let file_path: PathBuf = dir.join("added.txt");
+
+        let is_reintroduced =
+            |feature: &String| reintroduced.iter().any(|entry| &entry.feature == feature);
+
+        let mut lines: Vec<String> = added
+            .iter()
+            .filter(|feature| !added_disabled.contains(feature) && !is_reintroduced(feature))
+            .cloned()
+            .collect();
+
+        let new_disabled: Vec<&String> = added_disabled
+            .iter()
+            .filter(|feature| !is_reintroduced(feature))
+            .collect();
+        if !new_disabled.is_empty() {
+            lines.push(String::new());
+            lines.push(DISABLED_BY_DEFAULT_HEADING.to_string());
+            lines.extend(new_disabled.into_iter().cloned());
+        }
+
+        let mut by_last_seen: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for entry in reintroduced {
+            by_last_seen
+                .entry(entry.last_seen.as_str())
+                .or_default()
+                .push(entry.feature.as_str());
+        }
+        for (last_seen, features) in by_last_seen {
+            lines.push(String::new());
+            lines.push(format!("{} {}", REINTRODUCED_HEADING, last_seen));
+            lines.extend(features.into_iter().map(String::from));
+        }
+
+        fs::write(&file_path, lines.join("\n"))?;
+        Ok(())
+    }
+
+    fn save_feature_locations(
+        &self,
+        features: &BTreeMap<String, ExtractedFeature>,
+        dir: &Path,
+    ) -> Result<()> {
+        let file_path: PathBuf = dir.join("locations.txt");
+
+        // One line per feature: name, section, RVA and default state separated by tabs
+        let content: String = features
+            .iter()
+            .map(|(name, feature)| {
+                format!(
+                    "{}\t{}\t{:#010x}\t{}",
+                    name,
+                    feature.section,
+                    feature.rva,
+                    feature.default_state.map_or("unknown", FeatureState::label)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        fs::write(&file_path, content)?;
+        Ok(())
+    }
+
+    // The features whose default state locations.txt records as disabled, none when it is missing
+    fn load_disabled_features(&self, full_version: &str) -> Result<HashSet<String>> {
+        let file_path: PathBuf = self.archive.version_dir(full_version).join("locations.txt");
+
+        if !file_path.exists() {
+            return Ok(HashSet::new());
+        }
+
+        let content: String = fs::read_to_string(file_path)?;
+
+        // Name, section, RVA and default state separated by tabs, as save_feature_locations writes them
+        Ok(content
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<&str> = line.split('\t').collect();
+                (columns.len() == 4 && columns[3] == FeatureState::DisabledByDefault.label())
+                    .then(|| columns[0].to_string())
+            })
+            .collect())
+    }
+
+    // Load previous features - process lines the same way as extract_features.
+    // Without a previous version there is nothing to compare against, so every feature is new
     fn load_previous_features(
         &self,
-        previous_major_version: &str,
-        previous_full_version: &str,
+        previous: Option<&(String, String)>,
     ) -> Result<HashSet<String>> {
-        let file_path: PathBuf = PathBuf::from("Edge Canary")
+        let Some((previous_full_version, previous_major_version)) = previous else {
+            return Ok(HashSet::new());
+        };
+
+        let file_path: PathBuf = self
+            .archive
+            .root
+            .clone()
             .join(previous_major_version)
             .join(previous_full_version)
             .join("original.txt");
@@ -528,27 +1507,36 @@ impl EdgeUpdater {
         Ok(features)
     }
 
-    fn update_readme(&self, full_version: &str, added_features: &[String]) -> Result<()> {
+    fn update_readme(
+        &self,
+        full_version: &str,
+        added_features: &[String],
+        reintroduced: &[Reintroduction],
+        output: &Path,
+    ) -> Result<()> {
         let readme_content: String = fs::read_to_string("README.md")?;
 
         let current_time: String = Utc::now().format("%m/%d/%Y %H:%M:%S").to_string();
 
-        let added_list: String = added_features
+        let added_list: String = label_reintroduced(added_features, reintroduced)
             .iter()
             .map(|feature| format!("* {}\n", feature))
             .collect();
 
         let details_to_replace: String = format!(
-            "\n### <a href=\"https://github.com/SpyNetGirl/MSEdgeFeatures\"><img width=\"35\" src=\"https://github.com/HotCakeX/Harden-Windows-Security/raw/main/images/WebP/Edge%20Canary.webp\"></a> Latest Edge Canary version: {}\n\
+            "\n### <a href=\"{}\"><img width=\"35\" src=\"{}\"></a> Latest Edge Canary version: {}\n\
             ### Last processed at: {} (UTC+00:00)\n\
             <details>\n\
-            <summary>{} new features were added in the latest Edge Canary update</summary>\n\n\
+            <summary>{} new features were added in the latest Edge Canary update, {} of them re-added</summary>\n\n\
             <br>\n\n\
             {}\
             </details>\n",
+            self.config.repository_url(),
+            self.config.images.logo,
             full_version,
             current_time,
             added_features.len(),
+            reintroduced.len(),
             added_list
         );
 
@@ -556,16 +1544,16 @@ impl EdgeUpdater {
         let start_marker: &str = "<!-- Edge-Canary-Version:START -->";
         let end_marker: &str = "<!-- Edge-Canary-Version:END -->";
 
-        if let Some(start_pos) = readme_content.find(start_marker) {
-            if let Some(end_pos) = readme_content.find(end_marker) {
-                let start_index: usize = start_pos + start_marker.len();
-                let before: &str = &readme_content[..start_index];
-                let after: &str = &readme_content[end_pos..];
-                let updated_content: String = format!("{}{}{}", before, details_to_replace, after);
+        if let Some(start_pos) = readme_content.find(start_marker)
+            && let Some(end_pos) = readme_content.find(end_marker)
+        {
+            let start_index: usize = start_pos + start_marker.len();
+            let before: &str = &readme_content[..start_index];
+            let after: &str = &readme_content[end_pos..];
+            let updated_content: String = format!("{}{}{}", before, details_to_replace, after);
 
-                fs::write("README.md", updated_content.trim_end())?;
-                return Ok(());
-            }
+            fs::write(output, updated_content.trim_end())?;
+            return Ok(());
         }
 
         Err(anyhow!(
@@ -573,236 +1561,331 @@ impl EdgeUpdater {
         ))
     }
 
-    fn create_shortcut_maker(&self, full_version: &str, added_features: &[String]) -> Result<()> {
-        let features_string: String = added_features.join(",");
-        let pre_arguments: String = format!(
-            "--enable-features={}",
-            features_string.trim_end_matches(',')
-        );
+    // Write a PowerShell script that creates a .bat in Downloads launching Edge Canary as `spec` describes
+    fn create_shortcut_maker(
+        &self,
+        launcher_name: &str,
+        spec: &LaunchSpec,
+        file_path: &Path,
+    ) -> Result<()> {
+        let pre_arguments: String = spec.arguments(launchers::single_quote);
+
+        // Only needed when the launcher shares the profile of the Edge Canary that is already running
+        let stop_running: &str = if spec.closes_running_browser() {
+            r#"Get-Process | where-object -FilterScript {`$_.path -eq \`"C:\Users\`$UserName\AppData\Local\Microsoft\Edge SxS\Application\msedge.exe\`"} | ForEach-Object -Process {Stop-Process -Id `$_.id -Force -ErrorAction SilentlyContinue};"#
+        } else {
+            ""
+        };
 
         let content: String = format!(
             r#"
-$FullVersionToUse = "{}"
+$LauncherName = "{}"
 
 $Arguments = "{}"
 
 $content = @"
-powershell.exe -WindowStyle hidden -Command "`$UserSID = [System.Security.Principal.WindowsIdentity]::GetCurrent().user.value;`$UserName = (Get-LocalUser | where-object -FilterScript {{`$_.SID -eq `$UserSID}}).name;Get-Process | where-object -FilterScript {{`$_.path -eq \`"C:\Users\`$UserName\AppData\Local\Microsoft\Edge SxS\Application\msedge.exe\`"}} | ForEach-Object -Process {{Stop-Process -Id `$_.id -Force -ErrorAction SilentlyContinue}};& \`"C:\Users\`$UserName\AppData\Local\Microsoft\Edge SxS\Application\msedge.exe\`" $Arguments"
+powershell.exe -WindowStyle hidden -Command "`$UserSID = [System.Security.Principal.WindowsIdentity]::GetCurrent().user.value;`$UserName = (Get-LocalUser | where-object -FilterScript {{`$_.SID -eq `$UserSID}}).name;{}& \`"C:\Users\`$UserName\AppData\Local\Microsoft\Edge SxS\Application\msedge.exe\`" $Arguments"
 "@
 
-$content | Out-File -FilePath "C:\Users\$env:USERNAME\Downloads\EDGECAN Launcher $FullVersionToUse.bat"
+$content | Out-File -FilePath "C:\Users\$env:USERNAME\Downloads\EDGECAN Launcher $LauncherName.bat"
 "#,
-            full_version, pre_arguments
+            launcher_name, pre_arguments, stop_running
         );
 
-        fs::write("EdgeCanaryShortcutMaker.ps1", content)?;
+        fs::write(file_path, content)?;
         Ok(())
     }
 
-    fn commit_and_push(&self) -> Result<()> {
-        // Configure git
-        Command::new("git")
-            .args(["config", "--global", "user.email", "spynetgirl@outlook.com"])
-            .output()?;
-
-        Command::new("git")
-            .args(["config", "--global", "user.name", "HotCakeX"])
-            .output()?;
-
-        // Add all changes
-        Command::new("git").args(["add", "--all"]).output()?;
-
-        // Commit changes
-        Command::new("git")
-            .args(["commit", "-m", "Automated Update"])
-            .output()?;
-
-        // Push changes
-        Command::new("git").args(["push"]).output()?;
+    // Write the launchers of every other platform into `dir`
+    fn create_launchers(&self, launcher_name: &str, spec: &LaunchSpec, dir: &Path) -> Result<()> {
+        for launcher in launchers::generate(launcher_name, spec) {
+            fs::write(dir.join(launcher.file_name), launcher.content)?;
+        }
 
         Ok(())
     }
 
-    async fn create_github_release(
+    // Refresh each topic folder with the topic's features in this build, the version each first appeared in,
+    // and a shortcut maker that enables all of them
+    fn update_topic_shortcuts(
         &self,
         full_version: &str,
-        added: &[String],
-        removed: &[String],
+        features: &HashSet<String>,
+        history_index: &history::HistoryIndex,
+        staging: &mut Staging,
     ) -> Result<()> {
-        // Read GitHub token directly from environment variable
-        let github_token: String = std::env::var("GITHUB_TOKEN")
-            .map_err(|_| anyhow!("GITHUB_TOKEN environment variable is required"))?;
+        let topics_dir: &Path = Path::new(topics::TOPICS_DIR);
 
-        // Get latest commit SHA
-        let output = Command::new("git").args(["rev-parse", "HEAD"]).output()?;
-        let latest_sha: String = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let mut sorted_features: Vec<&String> = features.iter().collect();
+        sorted_features.sort();
 
-        let current_time: String = Utc::now().format("%m/%d/%Y %H:%M:%S").to_string();
+        for topic in topics::load(topics_dir)? {
+            let selected: Vec<String> = topic.select(sorted_features.iter().copied())?;
 
-        let added_list: String = if added.is_empty() {
-            "* \n".to_string()
-        } else {
-            added
-                .iter()
-                .map(|feature| format!("* {}\n", feature))
-                .collect()
-        };
+            let topic_dir: PathBuf = staging.dir_for_files(&topics_dir.join(&topic.name))?;
 
-        let removed_list: String = if removed.is_empty() {
-            "* \n".to_string()
-        } else {
-            removed
+            let lines: Vec<String> = selected
                 .iter()
-                .map(|feature| format!("* {}\n", feature))
-                .collect()
-        };
+                .map(|feature| {
+                    let first_seen: &str = history_index
+                        .features
+                        .get(feature)
+                        .map_or(full_version, |entry| entry.first_seen.as_str());
+                    format!("{}\t{}", feature, first_seen)
+                })
+                .collect();
+
+            let content: String = format!(
+                "The following are the {} feature flags found in Edge canary version {}, each with the version it first appeared in\n\
+                This list is regenerated with every Edge canary update.\n\n\
+                {}",
+                topic.title(),
+                full_version,
+                lines.join("\n")
+            );
+            fs::write(topic_dir.join("Features list.txt"), content)?;
 
-        let initial_body: String = format!(
-            "\n# <img width=\"35\" src=\"https://github.com/HotCakeX/Harden-Windows-Security/raw/main/images/WebP/Edge%20Canary.webp\"> Automated update\n\n\
-            ## Processed at: {} (UTC+00:00)\n\n\
-            Visit the GitHub's release section for full details on how to use it:\n\
-            https://github.com/SpyNetGirl/MSEdgeFeatures/releases/tag/{}\n\n\
-            ### {} New features were added\n\n\
-            {}\n\
-            <br>\n\n\
-            ### {} Features were removed\n\n\
-            {}\n\
-            <br>\n\n",
-            current_time,
-            full_version,
-            added.len(),
-            added_list,
-            removed.len(),
-            removed_list
-        );
+            self.create_shortcut_maker(
+                &format!("{} {}", topic.name, full_version),
+                &LaunchSpec::enabling(&selected),
+                &topic_dir.join("EdgeCanaryShortcutMaker.ps1"),
+            )?;
 
-        // Create release
-        let create_request: CreateReleaseRequest = CreateReleaseRequest {
-            tag_name: full_version.to_string(),
-            target_commitish: latest_sha,
-            name: format!("Edge Canary version {}", full_version),
-            body: initial_body,
-            draft: false,
-            prerelease: false,
-        };
+            println!("{}: {} features", topic.title(), selected.len());
+        }
 
-        // Make the API call with required User-Agent header
-        let response = self
-            .client
-            .post("https://api.github.com/repos/SpyNetGirl/MSEdgeFeatures/releases")
-            .header("Authorization", format!("token {}", github_token))
-            .header("User-Agent", "edge-canary-updater/1.0")
-            .json(&create_request)
-            .send()
-            .await?;
+        Ok(())
+    }
 
-        // Check if the response is successful
-        if !response.status().is_success() {
-            let status: reqwest::StatusCode = response.status();
-            let error_text: String = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow!("GitHub API error: {} - {}", status, error_text));
-        }
-
-        // Parse the response
-        let response_text: String = response.text().await?;
-        let release: GitHubRelease = serde_json::from_str(&response_text).map_err(|e| {
-            anyhow!(
-                "Failed to parse GitHub API response: {}. Response: {}",
-                e,
-                response_text
-            )
-        })?;
-
-        // Upload asset using gh CLI
-        let upload_output = Command::new("gh")
-            .args([
-                "release",
-                "upload",
-                full_version,
-                "./EdgeCanaryShortcutMaker.ps1",
-                "--clobber",
-            ])
-            .output()?;
+    // Release notes of a processed build, with the launchers in `assets_dir` as assets
+    fn build_release(&self, report: &BuildReport, assets_dir: &Path) -> Release {
+        let full_version: &str = &report.full_version;
+        let added: &[String] = &report.added;
+        let added_disabled: &[String] = &report.added_disabled;
+        let removed: &[String] = &report.removed;
+        let category_diffs: &[CategoryDiff] = &report.category_diffs;
 
-        if !upload_output.status.success() {
-            let stderr: String = String::from_utf8_lossy(&upload_output.stderr).to_string();
-            return Err(anyhow!("Failed to upload asset: {}", stderr));
-        }
+        let current_time: String = Utc::now().format("%m/%d/%Y %H:%M:%S").to_string();
+
+        let added_list: String = markdown_list(&label_reintroduced(added, &report.reintroduced));
+        let added_disabled_list: String = markdown_list(added_disabled);
+        let removed_list: String = markdown_list(removed);
+
+        // Removals in earlier builds that this build undid
+        let temporary_removals: Vec<String> = report
+            .reintroduced
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{}, removed in {} after last being seen in {}",
+                    entry.feature, entry.removed_in, entry.last_seen
+                )
+            })
+            .collect();
+        let temporary_removals_list: String = markdown_list(&temporary_removals);
+
+        let renamed: Vec<String> = report.renamed.iter().map(Rename::label).collect();
+        let renamed_list: String = markdown_list(&renamed);
+
+        let case_changed: Vec<String> = report.case_changed.iter().map(CaseChange::label).collect();
+        let case_changed_list: String = markdown_list(&case_changed);
+
+        let family_growth: Vec<String> = report
+            .family_growth
+            .iter()
+            .map(FamilyGrowth::label)
+            .collect();
+        let family_growth_list: String = markdown_list(&family_growth);
+
+        // One added and one removed section per extra string category
+        let category_sections: String = category_diffs
+            .iter()
+            .map(|category_diff| {
+                format!(
+                    "### {} New {} were added\n\n{}\n<br>\n\n### {} {} were removed\n\n{}\n<br>\n\n",
+                    category_diff.diff.added.len(),
+                    category_diff.category.title.to_lowercase(),
+                    markdown_list(&category_diff.diff.added),
+                    category_diff.diff.removed.len(),
+                    category_diff.category.title,
+                    markdown_list(&category_diff.diff.removed)
+                )
+            })
+            .collect();
+
+        // The launchers for other platforms were written next to the shortcut maker
+        let other_launchers: Vec<launchers::Launcher> =
+            launchers::generate(full_version, &LaunchSpec::enabling(added));
 
         let asset_name: &str = "EdgeCanaryShortcutMaker.ps1";
-        let asset_download_url: String = format!(
-            "https://github.com/SpyNetGirl/MSEdgeFeatures/releases/download/{}/{}",
-            full_version, asset_name
-        );
+        let asset_download_url: String = self.config.release_asset_url(full_version, asset_name);
 
-        // Update release body with download link
-        let final_body: String = format!(
-            "\n# <img width=\"35\" src=\"https://github.com/HotCakeX/Harden-Windows-Security/raw/main/images/WebP/Edge%20Canary.webp\"> Automated update\n\n\
+        let other_launchers_list: String = other_launchers
+            .iter()
+            .map(|launcher| {
+                format!(
+                    "* [{}]({}): {}\n",
+                    launcher.file_name,
+                    self.config
+                        .release_asset_url(full_version, launcher.file_name),
+                    launcher.description
+                )
+            })
+            .collect();
+
+        let body: String = format!(
+            "\n# <img width=\"35\" src=\"{}\"> Automated update\n\n\
             ## Processed at: {} (UTC+00:00)\n\n\
             ### {} New features were added\n\n\
             {}\n\
             <br>\n\n\
+            ### {} New features are disabled by default\n\n\
+            {}\n\
+            <br>\n\n\
             ### {} Features were removed\n\n\
             {}\n\
             <br>\n\n\
+            ### {} Earlier removals turned out to be temporary\n\n\
+            {}\n\
+            <br>\n\n\
+            ### {} Features were renamed\n\n\
+            {}\n\
+            <br>\n\n\
+            ### {} Features only changed case\n\n\
+            {}\n\
+            <br>\n\n\
+            ### {} Feature families gained members\n\n\
+            {}\n\
+            <br>\n\n\
+            {}\
             ### How to use the new features in this Edge canary update\n\n\
             1. First make sure your Edge canary is up to date\n\n\
             2. Copy and paste the code below in your PowerShell. NO admin privileges required. An Edge canary `.bat` file will be created in your Downloads folder. Double-click/tap on it to launch Edge canary with the features added in this update.\n\n\
             <br>\n\n\
             ```powershell\n\
             invoke-restMethod '{}' | Invoke-Expression\n\
-            ```\n\n",
+            ```\n\n\
+            <br>\n\n\
+            ### Launchers for other platforms\n\n\
+            Each one closes the running browser and starts it again with the features added in this update. Linux has no Canary channel, so Dev or Beta is used there.\n\n\
+            {}",
+            self.config.images.logo,
             current_time,
             added.len(),
             added_list,
+            added_disabled.len(),
+            added_disabled_list,
             removed.len(),
             removed_list,
-            asset_download_url
+            temporary_removals.len(),
+            temporary_removals_list,
+            renamed.len(),
+            renamed_list,
+            case_changed.len(),
+            case_changed_list,
+            family_growth.len(),
+            family_growth_list,
+            category_sections,
+            asset_download_url,
+            other_launchers_list
         );
 
-        let update_request: UpdateReleaseRequest = UpdateReleaseRequest { body: final_body };
-
-        // Update release
-        let update_response = self
-            .client
-            .patch(&format!(
-                "https://api.github.com/repos/SpyNetGirl/MSEdgeFeatures/releases/{}",
-                release.id
-            ))
-            .header("Authorization", format!("token {}", github_token))
-            .header("User-Agent", "edge-canary-updater/1.0")
-            .header("Content-Type", "application/json")
-            .json(&update_request)
-            .send()
-            .await?;
+        let mut assets: Vec<PathBuf> = vec![assets_dir.join(asset_name)];
+        assets.extend(
+            other_launchers
+                .iter()
+                .map(|launcher| assets_dir.join(launcher.file_name)),
+        );
 
-        if !update_response.status().is_success() {
-            let status: reqwest::StatusCode = update_response.status();
-            let error_text: String = update_response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow!(
-                "Failed to update GitHub release: {} - {}",
-                status,
-                error_text
-            ));
+        Release {
+            tag: full_version.to_string(),
+            name: format!("Edge Canary version {}", full_version),
+            body,
+            assets,
         }
-
-        Ok(())
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Validate that GITHUB_TOKEN environment variable exists
-    if std::env::var("GITHUB_TOKEN").is_err() {
-        return Err(anyhow!("GITHUB_TOKEN environment variable is required"));
+    let cli: Cli = Cli::parse();
+
+    let scan_options: ScanOptions = ScanOptions {
+        min_length: cli.min_length,
+        ..ScanOptions::default()
+    };
+    let mut config: Config = Config::load(cli.config.as_deref())?;
+    if let Some(data_dir) = cli.data_dir {
+        config.data_dir = data_dir;
     }
 
-    let updater: EdgeUpdater = EdgeUpdater::new();
-    updater.run().await
+    let publisher_kind: PublisherKind = if cli.no_publish {
+        PublisherKind::None
+    } else {
+        cli.publisher.unwrap_or(config.publish.publisher)
+    };
+
+    let updater: EdgeUpdater = EdgeUpdater::new(config, scan_options, cli.all_sections);
+
+    // Running without a subcommand keeps doing what the scheduled workflow expects
+    let command: CliCommand = cli.command.unwrap_or(CliCommand::Update { install: false });
+
+    match command {
+        CliCommand::Update { install } => {
+            let publisher: AnyPublisher =
+                AnyPublisher::new(publisher_kind, updater.client.clone(), &updater.config)?;
+            updater.run(install, &publisher).await
+        }
+        CliCommand::Scan {
+            dll,
+            edge_version,
+            force,
+        } => updater.run_offline(&dll, edge_version, force),
+        CliCommand::Diff {
+            from,
+            to,
+            format,
+            output,
+        } => updater.show_diff(&from, &to, format, output.as_deref()),
+        CliCommand::History { feature } => updater.show_history(&feature),
+        CliCommand::Search { pattern, version } => updater.search(&pattern, version),
+        CliCommand::Family {
+            name,
+            version,
+            output_dir,
+        } => updater.show_family(&name, version, &output_dir),
+        CliCommand::EnableFlags {
+            flags,
+            local_state,
+            dry_run,
+        } => updater.enable_in_local_state(flags, local_state, dry_run),
+        CliCommand::UndoEnableFlags { local_state } => {
+            updater.undo_enable_in_local_state(local_state)
+        }
+        CliCommand::Launcher {
+            version,
+            since,
+            enable,
+            disable,
+            force_fieldtrials,
+            user_data_dir,
+            output_dir,
+        } => updater.write_launchers(
+            version,
+            since,
+            LaunchSpec {
+                enable_features: enable,
+                disable_features: disable,
+                force_fieldtrials,
+                user_data_dir,
+            },
+            &output_dir,
+        ),
+        CliCommand::RebuildReadme => updater.rebuild_readme(),
+        CliCommand::Republish { version } => {
+            let publisher: AnyPublisher =
+                AnyPublisher::new(publisher_kind, updater.client.clone(), &updater.config)?;
+            updater.republish(&version, &publisher).await
+        }
+    }
 }
@@ -0,0 +1,352 @@
+use anyhow::{Result, anyhow};
+
+const IMAGE_SCN_CNT_INITIALIZED_DATA: u32 = 0x0000_0040;
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+const IMAGE_SCN_MEM_READ: u32 = 0x4000_0000;
+const IMAGE_SCN_MEM_WRITE: u32 = 0x8000_0000;
+
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// Index of the resource table in the optional header's data directories.
+pub const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+
+/// Resource type of VS_VERSIONINFO.
+pub const RT_VERSION: u32 = 16;
+
+/// One entry of the PE section table.
+#[derive(Clone, Debug)]
+pub struct Section {
+    pub name: String,
+    pub virtual_size: u32,
+    pub virtual_address: u32,
+    pub size_of_raw_data: u32,
+    pub pointer_to_raw_data: u32,
+    pub characteristics: u32,
+}
+
+impl Section {
+    /// Initialized, readable, non-writable, non-executable data such as `.rdata`.
+    pub fn is_read_only_data(&self) -> bool {
+        self.characteristics & IMAGE_SCN_CNT_INITIALIZED_DATA != 0
+            && self.characteristics & IMAGE_SCN_MEM_READ != 0
+            && self.characteristics & (IMAGE_SCN_MEM_WRITE | IMAGE_SCN_MEM_EXECUTE) == 0
+    }
+
+    /// Initialized data that is not code, i.e. anywhere a static object can live.
+    pub fn is_data(&self) -> bool {
+        self.characteristics & IMAGE_SCN_CNT_INITIALIZED_DATA != 0
+            && self.characteristics & IMAGE_SCN_MEM_EXECUTE == 0
+    }
+
+    /// The range of file offsets backing this section, without the alignment padding.
+    pub fn raw_range(&self, file_len: usize) -> std::ops::Range<usize> {
+        let start: usize = (self.pointer_to_raw_data as usize).min(file_len);
+        let mut size: usize = self.size_of_raw_data as usize;
+        if self.virtual_size != 0 {
+            size = size.min(self.virtual_size as usize);
+        }
+        start..(start + size).min(file_len)
+    }
+}
+
+/// Just enough of a PE/COFF image to map file offsets to sections and RVAs.
+#[derive(Debug)]
+pub struct PeFile {
+    pub is_64bit: bool,
+    pub image_base: u64,
+    /// (RVA, size) of each optional header data directory
+    pub data_directories: Vec<(u32, u32)>,
+    pub sections: Vec<Section>,
+}
+
+impl PeFile {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.get(0..2) != Some(b"MZ") {
+            return Err(anyhow!("Not a PE file: missing MZ signature"));
+        }
+
+        let pe_offset: usize = read_u32(data, 0x3C)? as usize;
+        if data.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+            return Err(anyhow!("Not a PE file: missing PE signature"));
+        }
+
+        // COFF file header follows the 4-byte signature
+        let coff_offset: usize = pe_offset + 4;
+        let number_of_sections: usize = read_u16(data, coff_offset + 2)? as usize;
+        let size_of_optional_header: usize = read_u16(data, coff_offset + 16)? as usize;
+
+        // The optional header's magic decides the width of the image base and of every pointer
+        let optional_offset: usize = coff_offset + 20;
+        let (is_64bit, image_base, directories_offset): (bool, u64, usize) =
+            match read_u16(data, optional_offset)? {
+                PE32_MAGIC => (
+                    false,
+                    read_u32(data, optional_offset + 28)? as u64,
+                    optional_offset + 92,
+                ),
+                PE32_PLUS_MAGIC => (
+                    true,
+                    read_u64(data, optional_offset + 24)?,
+                    optional_offset + 108,
+                ),
+                magic => return Err(anyhow!("Unknown optional header magic {:#x}", magic)),
+            };
+
+        // NumberOfRvaAndSizes is followed by that many (RVA, size) pairs
+        let number_of_directories: usize = read_u32(data, directories_offset)? as usize;
+        let mut data_directories: Vec<(u32, u32)> = Vec::with_capacity(number_of_directories);
+        for index in 0..number_of_directories.min(16) {
+            let offset: usize = directories_offset + 4 + index * 8;
+            data_directories.push((read_u32(data, offset)?, read_u32(data, offset + 4)?));
+        }
+
+        let section_table_offset: usize = coff_offset + 20 + size_of_optional_header;
+        let mut sections: Vec<Section> = Vec::with_capacity(number_of_sections);
+
+        for index in 0..number_of_sections {
+            let offset: usize = section_table_offset + index * SECTION_HEADER_SIZE;
+            let raw_name: &[u8] = data
+                .get(offset..offset + 8)
+                .ok_or_else(|| anyhow!("Section table is truncated"))?;
+
+            let name: String = String::from_utf8_lossy(raw_name)
+                .trim_end_matches('\0')
+                .to_string();
+
+            sections.push(Section {
+                name,
+                virtual_size: read_u32(data, offset + 8)?,
+                virtual_address: read_u32(data, offset + 12)?,
+                size_of_raw_data: read_u32(data, offset + 16)?,
+                pointer_to_raw_data: read_u32(data, offset + 20)?,
+                characteristics: read_u32(data, offset + 36)?,
+            });
+        }
+
+        Ok(Self {
+            is_64bit,
+            image_base,
+            data_directories,
+            sections,
+        })
+    }
+
+    /// Maps an RVA to the file offset that backs it, if any section does.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        self.sections.iter().find_map(|section| {
+            let delta: u32 = rva.checked_sub(section.virtual_address)?;
+            (delta < section.size_of_raw_data)
+                .then(|| section.pointer_to_raw_data as usize + delta as usize)
+        })
+    }
+
+    /// Returns the bytes of the first resource of type `resource_type`, whatever its name and language.
+    pub fn find_resource<'a>(
+        &self,
+        data: &'a [u8],
+        resource_type: u32,
+    ) -> Result<Option<&'a [u8]>> {
+        let Some(&(root_rva, _)) = self.data_directories.get(IMAGE_DIRECTORY_ENTRY_RESOURCE) else {
+            return Ok(None);
+        };
+        if root_rva == 0 {
+            return Ok(None);
+        }
+
+        let root: usize = self
+            .rva_to_offset(root_rva)
+            .ok_or_else(|| anyhow!("Resource directory is outside every section"))?;
+
+        // Type -> name -> language -> data entry
+        let Some(by_name) = find_resource_entry(data, root, root, Some(resource_type))? else {
+            return Ok(None);
+        };
+        let Some(by_language) = find_resource_entry(data, root, by_name, None)? else {
+            return Ok(None);
+        };
+        let Some(data_entry) = find_resource_entry(data, root, by_language, None)? else {
+            return Ok(None);
+        };
+
+        let data_rva: u32 = read_u32(data, data_entry)?;
+        let size: usize = read_u32(data, data_entry + 4)? as usize;
+        let start: usize = self
+            .rva_to_offset(data_rva)
+            .ok_or_else(|| anyhow!("Resource data is outside every section"))?;
+
+        data.get(start..start + size)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Resource data is truncated"))
+    }
+
+    /// Size in bytes of a pointer stored in the image.
+    pub fn pointer_size(&self) -> usize {
+        if self.is_64bit { 8 } else { 4 }
+    }
+
+    /// Reads a pointer-sized value at `offset`, zero-extended to 64 bits.
+    pub fn read_pointer(&self, data: &[u8], offset: usize) -> Result<u64> {
+        if self.is_64bit {
+            read_u64(data, offset)
+        } else {
+            read_u32(data, offset).map(u64::from)
+        }
+    }
+}
+
+// Looks up an entry of the resource directory at `directory`, by ID or the first one when `id` is None,
+// and returns the file offset of what it points to
+fn find_resource_entry(
+    data: &[u8],
+    root: usize,
+    directory: usize,
+    id: Option<u32>,
+) -> Result<Option<usize>> {
+    let named: usize = read_u16(data, directory + 12)? as usize;
+    let ids: usize = read_u16(data, directory + 14)? as usize;
+
+    for index in 0..named + ids {
+        let entry: usize = directory + 16 + index * 8;
+        let name: u32 = read_u32(data, entry)?;
+        let target: u32 = read_u32(data, entry + 4)?;
+
+        if id.is_some_and(|id| name != id) {
+            continue;
+        }
+
+        // The high bit marks a subdirectory; offsets are relative to the root directory
+        return Ok(Some(root + (target & 0x7FFF_FFFF) as usize));
+    }
+
+    Ok(None)
+}
+
+pub fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| anyhow!("Unexpected end of PE data at offset {:#x}", offset))
+}
+
+pub fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| anyhow!("Unexpected end of PE data at offset {:#x}", offset))
+}
+
+pub fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+        .ok_or_else(|| anyhow!("Unexpected end of PE data at offset {:#x}", offset))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    const PE_OFFSET: usize = 0x40;
+    // 112 bytes of PE32+ fields, then 16 data directories
+    const OPTIONAL_HEADER_SIZE: usize = 112 + 16 * 8;
+
+    pub(crate) fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    // Where the synthetic image's .rsrc section, which the resource directory points at, starts in the file and in memory
+    pub(crate) const RSRC_OFFSET: usize = 0x600;
+    pub(crate) const RSRC_RVA: u32 = 0x3000;
+
+    // A PE32+ header with an .rdata section at RVA 0x2000, backed by file offset 0x400, and an
+    // empty .rsrc section at RVA 0x3000, backed by file offset 0x600
+    pub(crate) fn synthetic_pe() -> Vec<u8> {
+        let mut data: Vec<u8> = vec![0; 0x800];
+        data[0..2].copy_from_slice(b"MZ");
+        put_u32(&mut data, 0x3C, PE_OFFSET as u32);
+        data[PE_OFFSET..PE_OFFSET + 4].copy_from_slice(b"PE\0\0");
+
+        let coff_offset: usize = PE_OFFSET + 4;
+        put_u16(&mut data, coff_offset + 2, 2);
+        put_u16(&mut data, coff_offset + 16, OPTIONAL_HEADER_SIZE as u16);
+
+        let optional_offset: usize = coff_offset + 20;
+        put_u16(&mut data, optional_offset, PE32_PLUS_MAGIC);
+        data[optional_offset + 24..optional_offset + 32]
+            .copy_from_slice(&0x1_8000_0000u64.to_le_bytes());
+        put_u32(&mut data, optional_offset + 108, 16);
+        put_u32(
+            &mut data,
+            optional_offset + 112 + IMAGE_DIRECTORY_ENTRY_RESOURCE * 8,
+            RSRC_RVA,
+        );
+
+        let section_offset: usize = optional_offset + OPTIONAL_HEADER_SIZE;
+        data[section_offset..section_offset + 6].copy_from_slice(b".rdata");
+        put_u32(&mut data, section_offset + 8, 0x100);
+        put_u32(&mut data, section_offset + 12, 0x2000);
+        put_u32(&mut data, section_offset + 16, 0x200);
+        put_u32(&mut data, section_offset + 20, 0x400);
+        put_u32(
+            &mut data,
+            section_offset + 36,
+            IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ,
+        );
+
+        let rsrc_offset: usize = section_offset + SECTION_HEADER_SIZE;
+        data[rsrc_offset..rsrc_offset + 5].copy_from_slice(b".rsrc");
+        put_u32(&mut data, rsrc_offset + 8, 0x200);
+        put_u32(&mut data, rsrc_offset + 12, RSRC_RVA);
+        put_u32(&mut data, rsrc_offset + 16, 0x200);
+        put_u32(&mut data, rsrc_offset + 20, RSRC_OFFSET as u32);
+        put_u32(
+            &mut data,
+            rsrc_offset + 36,
+            IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ,
+        );
+
+        data
+    }
+
+    #[test]
+    fn parses_a_synthetic_header() {
+        let pe: PeFile = PeFile::parse(&synthetic_pe()).unwrap();
+
+        assert!(pe.is_64bit);
+        assert_eq!(pe.image_base, 0x1_8000_0000);
+        assert_eq!(pe.data_directories.len(), 16);
+        assert_eq!(
+            pe.data_directories[IMAGE_DIRECTORY_ENTRY_RESOURCE],
+            (RSRC_RVA, 0)
+        );
+        assert_eq!(pe.sections.len(), 2);
+        assert_eq!(pe.sections[0].name, ".rdata");
+        assert!(pe.sections[0].is_read_only_data());
+        assert_eq!(pe.sections[0].raw_range(0x800), 0x400..0x500);
+        assert_eq!(pe.sections[1].name, ".rsrc");
+    }
+
+    #[test]
+    fn maps_rvas_inside_sections_only() {
+        let pe: PeFile = PeFile::parse(&synthetic_pe()).unwrap();
+
+        assert_eq!(pe.rva_to_offset(0x2000), Some(0x400));
+        assert_eq!(pe.rva_to_offset(0x2010), Some(0x410));
+        assert_eq!(pe.rva_to_offset(0x1FFF), None);
+        assert_eq!(pe.rva_to_offset(0x2200), None);
+        assert_eq!(pe.rva_to_offset(RSRC_RVA + 4), Some(RSRC_OFFSET + 4));
+    }
+
+    #[test]
+    fn rejects_data_without_signatures() {
+        assert!(PeFile::parse(b"not a pe file").is_err());
+
+        let mut data: Vec<u8> = synthetic_pe();
+        data[PE_OFFSET] = b'X';
+        assert!(PeFile::parse(&data).is_err());
+    }
+}
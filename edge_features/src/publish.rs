@@ -0,0 +1,468 @@
+use crate::config::Config;
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use reqwest::{Body, Client, IntoUrl, Method, RequestBuilder, Response, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where a processed build is published to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PublisherKind {
+    /// Commit and push the working tree, then create a GitHub release
+    Github,
+    /// Write the release notes and assets to a local directory
+    Filesystem,
+    /// Publish nothing
+    None,
+}
+
+/// A release of one processed build: its notes and the files attached to it.
+#[derive(Clone, Debug)]
+pub struct Release {
+    pub tag: String,
+    pub name: String,
+    /// Markdown release notes
+    pub body: String,
+    /// Files to attach, uploaded under their file names
+    pub assets: Vec<PathBuf>,
+}
+
+/// Publishes a build once its files were written to the working tree.
+pub trait Publisher {
+    /// Records the newly processed build's files before its release is published. Only `update`
+    /// calls it, republishing leaves the working tree alone.
+    fn commit_build(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn publish(&self, release: &Release) -> Result<()>;
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GitHubRelease {
+    id: u64,
+    tag_name: String,
+    name: String,
+    body: String,
+    draft: bool,
+    prerelease: bool,
+    target_commitish: String,
+    /// Hypermedia template of the uploads endpoint, e.g. https://uploads.github.com/repos/o/r/releases/1/assets{?name,label}
+    upload_url: String,
+    #[serde(default)]
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GitHubAsset {
+    id: u64,
+    name: String,
+    /// "sha256:<hex>" of the content, missing on assets uploaded before GitHub recorded it
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateReleaseRequest {
+    tag_name: String,
+    target_commitish: String,
+    name: String,
+    body: String,
+    draft: bool,
+    prerelease: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UpdateReleaseRequest {
+    name: String,
+    body: String,
+}
+
+/// Commits and pushes the working tree of an update, and creates or updates the release through the
+/// REST API at `api_base_url`, so GitHub Enterprise or a local mock server can stand in for api.github.com.
+/// Publishing the same release again only changes what differs, so an interrupted run can be redone.
+pub struct GitHubPublisher {
+    client: Client,
+    api_base_url: String,
+    owner: String,
+    repo: String,
+    token: String,
+    user_name: String,
+    user_email: String,
+}
+
+impl GitHubPublisher {
+    /// Fails when GITHUB_TOKEN is not set, so that is known before a build is processed.
+    pub fn new(client: Client, config: &Config) -> Result<Self> {
+        let token: String = std::env::var("GITHUB_TOKEN")
+            .map_err(|_| anyhow!("GITHUB_TOKEN environment variable is required"))?;
+
+        Ok(Self {
+            client,
+            api_base_url: config
+                .publish
+                .api_base_url
+                .trim_end_matches('/')
+                .to_string(),
+            owner: config.repository.owner.clone(),
+            repo: config.repository.name.clone(),
+            token,
+            user_name: config.git.user_name.clone(),
+            user_email: config.git.user_email.clone(),
+        })
+    }
+
+    fn commit_and_push(&self) -> Result<()> {
+        // Configure git
+        Command::new("git")
+            .args(["config", "--global", "user.email", &self.user_email])
+            .output()?;
+
+        Command::new("git")
+            .args(["config", "--global", "user.name", &self.user_name])
+            .output()?;
+
+        // Add all changes
+        Command::new("git").args(["add", "--all"]).output()?;
+
+        // Commit changes
+        Command::new("git")
+            .args(["commit", "-m", "Automated Update"])
+            .output()?;
+
+        // Push changes
+        Command::new("git").args(["push"]).output()?;
+
+        Ok(())
+    }
+
+    // A request to the API with the token and the User-Agent GitHub requires
+    fn request(&self, method: Method, url: impl IntoUrl) -> RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("Authorization", format!("token {}", self.token))
+            .header("User-Agent", "edge-canary-updater/1.0")
+    }
+
+    // The release of `tag`, or None when the tag has no release yet
+    async fn find_release(&self, tag: &str) -> Result<Option<GitHubRelease>> {
+        let response: Response = self
+            .request(
+                Method::GET,
+                format!(
+                    "{}/repos/{}/{}/releases/tags/{}",
+                    self.api_base_url, self.owner, self.repo, tag
+                ),
+            )
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let response: Response = check_response(
+            response,
+            &format!("Failed to look up the release of {}", tag),
+        )
+        .await?;
+        Ok(Some(parse_response(response).await?))
+    }
+
+    async fn update_release(
+        &self,
+        existing: &GitHubRelease,
+        release: &Release,
+    ) -> Result<GitHubRelease> {
+        let update_request: UpdateReleaseRequest = UpdateReleaseRequest {
+            name: release.name.clone(),
+            body: release.body.clone(),
+        };
+
+        let response: Response = self
+            .request(
+                Method::PATCH,
+                format!(
+                    "{}/repos/{}/{}/releases/{}",
+                    self.api_base_url, self.owner, self.repo, existing.id
+                ),
+            )
+            .json(&update_request)
+            .send()
+            .await?;
+
+        parse_response(check_response(response, "Failed to update GitHub release").await?).await
+    }
+
+    async fn create_release(&self, release: &Release) -> Result<GitHubRelease> {
+        // Get latest commit SHA
+        let output = Command::new("git").args(["rev-parse", "HEAD"]).output()?;
+        let latest_sha: String = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let create_request: CreateReleaseRequest = CreateReleaseRequest {
+            tag_name: release.tag.clone(),
+            target_commitish: latest_sha,
+            name: release.name.clone(),
+            body: release.body.clone(),
+            draft: false,
+            prerelease: false,
+        };
+
+        let response = self
+            .request(
+                Method::POST,
+                format!(
+                    "{}/repos/{}/{}/releases",
+                    self.api_base_url, self.owner, self.repo
+                ),
+            )
+            .json(&create_request)
+            .send()
+            .await?;
+
+        parse_response(check_response(response, "GitHub API error").await?).await
+    }
+
+    // Uploads each asset through the uploads endpoint. One already attached under the same name
+    // is kept when its content is the same and replaced otherwise.
+    async fn upload_assets(&self, release: &Release, published: &GitHubRelease) -> Result<()> {
+        // The template's {?name,label} part is filled in with query parameters instead
+        let upload_url: &str = published
+            .upload_url
+            .split('{')
+            .next()
+            .unwrap_or(&published.upload_url);
+
+        for asset in &release.assets {
+            let name: String = asset
+                .file_name()
+                .ok_or_else(|| anyhow!("Asset {} has no file name", asset.display()))?
+                .to_string_lossy()
+                .to_string();
+
+            let file: tokio::fs::File = tokio::fs::File::open(asset)
+                .await
+                .map_err(|e| anyhow!("Failed to open {}: {}", asset.display(), e))?;
+            let length: u64 = file.metadata().await?.len();
+
+            if let Some(existing) = published
+                .assets
+                .iter()
+                .find(|existing| existing.name == name)
+            {
+                if existing.digest.as_deref() == Some(sha256_digest(asset).await?.as_str()) {
+                    println!("{} is already uploaded", name);
+                    continue;
+                }
+                self.delete_asset(existing).await?;
+            }
+
+            // Streamed from disk, the uploads endpoint needs the length up front since it does not take chunked bodies
+            let response: Response = self
+                .request(
+                    Method::POST,
+                    Url::parse_with_params(upload_url, [("name", &name)])?,
+                )
+                .header("Content-Type", content_type(asset))
+                .header("Content-Length", length)
+                .body(Body::from(file))
+                .send()
+                .await?;
+
+            let response: Response =
+                check_response(response, &format!("Failed to upload {}", name)).await?;
+            let uploaded: GitHubAsset = parse_response(response).await?;
+
+            println!("Uploaded {} as asset {}", uploaded.name, uploaded.id);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_asset(&self, asset: &GitHubAsset) -> Result<()> {
+        let response: Response = self
+            .request(
+                Method::DELETE,
+                format!(
+                    "{}/repos/{}/{}/releases/assets/{}",
+                    self.api_base_url, self.owner, self.repo, asset.id
+                ),
+            )
+            .send()
+            .await?;
+
+        // Already gone is as good as deleted
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        check_response(response, &format!("Failed to delete asset {}", asset.name)).await?;
+
+        Ok(())
+    }
+}
+
+// Turns a response with an error status into an error carrying the status and body
+async fn check_response(response: Response, context: &str) -> Result<Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status: StatusCode = response.status();
+    let error_text: String = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    Err(anyhow!("{}: {} - {}", context, status, error_text))
+}
+
+async fn parse_response<T: serde::de::DeserializeOwned>(response: Response) -> Result<T> {
+    let response_text: String = response.text().await?;
+    serde_json::from_str(&response_text).map_err(|e| {
+        anyhow!(
+            "Failed to parse GitHub API response: {}. Response: {}",
+            e,
+            response_text
+        )
+    })
+}
+
+// A file's SHA-256 in the form GitHub reports asset digests in
+async fn sha256_digest(path: &Path) -> Result<String> {
+    let hash = Sha256::digest(tokio::fs::read(path).await?);
+    let hex: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+    Ok(format!("sha256:{}", hex))
+}
+
+/// Media type of a release asset, by its extension.
+fn content_type(path: &Path) -> &'static str {
+    let extension: String = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "ps1" | "cmd" | "bat" | "command" | "sh" | "txt" => "text/plain; charset=utf-8",
+        "desktop" => "application/x-desktop",
+        "md" => "text/markdown; charset=utf-8",
+        "json" => "application/json",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+impl Publisher for GitHubPublisher {
+    fn commit_build(&self) -> Result<()> {
+        self.commit_and_push()
+    }
+
+    async fn publish(&self, release: &Release) -> Result<()> {
+        let published: GitHubRelease = match self.find_release(&release.tag).await? {
+            Some(existing) => {
+                let updated: GitHubRelease = self.update_release(&existing, release).await?;
+                println!(
+                    "Updated GitHub release {} with id {}",
+                    updated.tag_name, updated.id
+                );
+                updated
+            }
+            None => {
+                let created: GitHubRelease = self.create_release(release).await?;
+                println!(
+                    "Created GitHub release {} with id {}",
+                    created.tag_name, created.id
+                );
+                created
+            }
+        };
+
+        self.upload_assets(release, &published).await
+    }
+}
+
+/// Writes each release to `<dir>/<tag>/`: the notes as release.md next to copies of the assets.
+pub struct FilesystemPublisher {
+    dir: PathBuf,
+}
+
+impl FilesystemPublisher {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+impl Publisher for FilesystemPublisher {
+    async fn publish(&self, release: &Release) -> Result<()> {
+        let release_dir: PathBuf = self.dir.join(&release.tag);
+        fs::create_dir_all(&release_dir)
+            .map_err(|e| anyhow!("Failed to create {}: {}", release_dir.display(), e))?;
+
+        fs::write(
+            release_dir.join("release.md"),
+            format!("# {}\n{}", release.name, release.body),
+        )?;
+
+        for asset in &release.assets {
+            let file_name: &Path = Path::new(
+                asset
+                    .file_name()
+                    .ok_or_else(|| anyhow!("Asset {} has no file name", asset.display()))?,
+            );
+            fs::copy(asset, release_dir.join(file_name))
+                .map_err(|e| anyhow!("Failed to copy {}: {}", asset.display(), e))?;
+        }
+
+        println!("Wrote release {} to {}", release.tag, release_dir.display());
+        Ok(())
+    }
+}
+
+/// The publisher picked on the command line or in the config file.
+pub enum AnyPublisher {
+    Github(GitHubPublisher),
+    Filesystem(FilesystemPublisher),
+    None(NoopPublisher),
+}
+
+impl AnyPublisher {
+    pub fn new(kind: PublisherKind, client: Client, config: &Config) -> Result<Self> {
+        Ok(match kind {
+            PublisherKind::Github => Self::Github(GitHubPublisher::new(client, config)?),
+            PublisherKind::Filesystem => {
+                Self::Filesystem(FilesystemPublisher::new(config.publish.output_dir.clone()))
+            }
+            PublisherKind::None => Self::None(NoopPublisher),
+        })
+    }
+}
+
+impl Publisher for AnyPublisher {
+    fn commit_build(&self) -> Result<()> {
+        match self {
+            Self::Github(publisher) => publisher.commit_build(),
+            Self::Filesystem(publisher) => publisher.commit_build(),
+            Self::None(publisher) => publisher.commit_build(),
+        }
+    }
+
+    async fn publish(&self, release: &Release) -> Result<()> {
+        match self {
+            Self::Github(publisher) => publisher.publish(release).await,
+            Self::Filesystem(publisher) => publisher.publish(release).await,
+            Self::None(publisher) => publisher.publish(release).await,
+        }
+    }
+}
+
+/// Leaves the processed build in the working tree without publishing it anywhere.
+pub struct NoopPublisher;
+
+impl Publisher for NoopPublisher {
+    async fn publish(&self, _release: &Release) -> Result<()> {
+        println!("Publishing is disabled, not committing or creating a release");
+        Ok(())
+    }
+}
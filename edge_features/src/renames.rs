@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+
+/// A removed name and the added name that most likely replaced it.
+#[derive(Clone, Debug)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+impl Rename {
+    pub fn label(&self) -> String {
+        format!("{} → {}", self.from, self.to)
+    }
+}
+
+// Pairs scoring below this are treated as unrelated names
+const MIN_SCORE: f64 = 0.75;
+
+/// Pairs removed names with added names that look like renames of them: the same name with a
+/// different version suffix or casing, or names that are a few edits apart and share most of
+/// their camel-case tokens. Every name is used in at most one pair, best scoring pairs first.
+pub fn detect(removed: &[String], added: &[String]) -> Vec<Rename> {
+    let added_tokens: Vec<HashSet<String>> = added.iter().map(|name| tokens(name)).collect();
+
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (removed_index, from) in removed.iter().enumerate() {
+        let from_tokens: HashSet<String> = tokens(from);
+
+        for (added_index, to) in added.iter().enumerate() {
+            // Names without a token in common besides the "ms" prefix are not worth an edit distance
+            if !from_tokens
+                .intersection(&added_tokens[added_index])
+                .any(|token| token != "ms")
+            {
+                continue;
+            }
+
+            let score: f64 = score(from, to, &from_tokens, &added_tokens[added_index]);
+            if score >= MIN_SCORE {
+                candidates.push((score, removed_index, added_index));
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| removed[a.1].cmp(&removed[b.1]))
+            .then_with(|| added[a.2].cmp(&added[b.2]))
+    });
+
+    let mut used_removed: HashSet<usize> = HashSet::new();
+    let mut used_added: HashSet<usize> = HashSet::new();
+    let mut renames: Vec<Rename> = Vec::new();
+
+    for (_, removed_index, added_index) in candidates {
+        if used_removed.contains(&removed_index) || used_added.contains(&added_index) {
+            continue;
+        }
+        used_removed.insert(removed_index);
+        used_added.insert(added_index);
+        renames.push(Rename {
+            from: removed[removed_index].clone(),
+            to: added[added_index].clone(),
+        });
+    }
+
+    renames.sort_by(|a, b| a.from.cmp(&b.from));
+    renames
+}
+
+fn score(from: &str, to: &str, from_tokens: &HashSet<String>, to_tokens: &HashSet<String>) -> f64 {
+    // msFooBarV1 → msFooBarV2 and msFooBar → MSFooBar2 are renames for certain
+    if strip_version_suffix(from).eq_ignore_ascii_case(strip_version_suffix(to)) {
+        return 1.0;
+    }
+
+    let from_lower: String = from.to_ascii_lowercase();
+    let to_lower: String = to.to_ascii_lowercase();
+    let longest: usize = from_lower.len().max(to_lower.len());
+    let edit_similarity: f64 =
+        1.0 - edit_distance(from_lower.as_bytes(), to_lower.as_bytes()) as f64 / longest as f64;
+
+    let shared: usize = from_tokens.intersection(to_tokens).count();
+    let token_similarity: f64 = shared as f64 / from_tokens.union(to_tokens).count() as f64;
+
+    (edit_similarity + token_similarity) / 2.0
+}
+
+/// The lowercase camel-case tokens of a name, see `split_camel_case`.
+pub fn tokens(name: &str) -> HashSet<String> {
+    split_camel_case(name)
+        .into_iter()
+        .map(|token| token.to_ascii_lowercase())
+        .collect()
+}
+
+/// Splits a name at lowercase-to-uppercase, acronym-to-word and letter-to-digit boundaries,
+/// keeping the original casing: "MSEdgeUI2" gives MS, Edge, UI and 2.
+pub fn split_camel_case(name: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut parts: Vec<&str> = Vec::new();
+    let mut start: usize = 0;
+
+    for window in 1..chars.len() {
+        let (index, c) = chars[window];
+        let p: char = chars[window - 1].1;
+        let next_is_lowercase: bool = chars
+            .get(window + 1)
+            .is_some_and(|(_, n)| n.is_ascii_lowercase());
+
+        let boundary: bool = (p.is_ascii_lowercase() && c.is_ascii_uppercase())
+            || (p.is_ascii_uppercase() && c.is_ascii_uppercase() && next_is_lowercase)
+            || (p.is_ascii_digit() != c.is_ascii_digit());
+        if boundary {
+            parts.push(&name[start..index]);
+            start = index;
+        }
+    }
+
+    if start < name.len() {
+        parts.push(&name[start..]);
+    }
+
+    parts
+}
+
+// "msFooBarV2" and "msFooBar2" both become "msFooBar"
+fn strip_version_suffix(name: &str) -> &str {
+    let without_digits: &str = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_digits.len() == name.len() {
+        return name;
+    }
+
+    without_digits
+        .strip_suffix(['V', 'v'])
+        .unwrap_or(without_digits)
+}
+
+// Levenshtein distance over bytes, names are ASCII
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, &ca) in a.iter().enumerate() {
+        let mut diagonal: usize = row[0];
+        row[0] = i + 1;
+
+        for (j, &cb) in b.iter().enumerate() {
+            let above: usize = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn labels(renames: &[Rename]) -> Vec<String> {
+        renames.iter().map(Rename::label).collect()
+    }
+
+    #[test]
+    fn pairs_version_bumps_and_case_changes() {
+        let renames: Vec<Rename> = detect(
+            &names(&["msToolbarDeclutterV1", "msEdgeSidebar"]),
+            &names(&["msToolbarDeclutterV2", "MSEdgeSidebar2"]),
+        );
+
+        assert_eq!(
+            labels(&renames),
+            [
+                "msEdgeSidebar → MSEdgeSidebar2",
+                "msToolbarDeclutterV1 → msToolbarDeclutterV2"
+            ]
+        );
+    }
+
+    #[test]
+    fn pairs_close_names_sharing_tokens() {
+        let renames: Vec<Rename> = detect(
+            &names(&["msWalletCheckoutAutofill"]),
+            &names(&["msWalletCheckoutAutoFillV2", "msShoppingCoupons"]),
+        );
+
+        assert_eq!(
+            labels(&renames),
+            ["msWalletCheckoutAutofill → msWalletCheckoutAutoFillV2"]
+        );
+    }
+
+    #[test]
+    fn leaves_unrelated_names_alone() {
+        let renames: Vec<Rename> = detect(
+            &names(&["msEdgeSidebar", "msFooBar"]),
+            &names(&["msCopilotComposer", "msBarBaz"]),
+        );
+
+        assert!(renames.is_empty());
+    }
+
+    #[test]
+    fn uses_each_name_once() {
+        let renames: Vec<Rename> = detect(
+            &names(&["msSidebarPinV1"]),
+            &names(&["msSidebarPinV3", "msSidebarPinV2"]),
+        );
+
+        assert_eq!(labels(&renames), ["msSidebarPinV1 → msSidebarPinV2"]);
+    }
+
+    #[test]
+    fn splits_at_case_acronym_and_digit_boundaries() {
+        assert_eq!(split_camel_case("MSEdgeUI2"), ["MS", "Edge", "UI", "2"]);
+        assert_eq!(
+            split_camel_case("msToolbarDeclutter"),
+            ["ms", "Toolbar", "Declutter"]
+        );
+    }
+}
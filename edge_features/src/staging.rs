@@ -0,0 +1,295 @@
+use anyhow::{Result, anyhow};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Prefix of the staging directory, which is only left behind when the process itself is killed.
+pub const STAGING_PREFIX: &str = ".edge_features-staging-";
+
+/// What a staged path becomes once the staging is committed.
+struct Entry {
+    /// Which of the staging directories it is in
+    dir: usize,
+    staged: PathBuf,
+    destination: PathBuf,
+    /// The files directly inside `staged` are moved into `destination` one by one, so a
+    /// directory that holds other files too can receive them
+    file_by_file: bool,
+}
+
+/// Outputs of one run, written to temporary directories first and moved into place together
+/// once all of them are there, so a run that fails midway leaves the previous state untouched.
+pub struct Staging {
+    /// Each staging directory with the directory it was created in
+    dirs: Vec<(PathBuf, TempDir)>,
+    entries: Vec<Entry>,
+}
+
+impl Staging {
+    /// Creates a staging directory in each of `parents`. An output is staged in the deepest parent
+    /// its destination is inside of, or the first one, so moving it into place is a rename even
+    /// when a parent, like the archive root, is on a filesystem of its own.
+    pub fn new(parents: &[&Path]) -> Result<Self> {
+        let mut dirs: Vec<(PathBuf, TempDir)> = Vec::new();
+        for parent in parents {
+            fs::create_dir_all(parent)?;
+            let dir: TempDir = tempfile::Builder::new()
+                .prefix(STAGING_PREFIX)
+                .tempdir_in(parent)
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to create a staging directory in {}: {}",
+                        parent.display(),
+                        e
+                    )
+                })?;
+            dirs.push((parent.to_path_buf(), dir));
+        }
+
+        Ok(Self {
+            dirs,
+            entries: Vec::new(),
+        })
+    }
+
+    // The staging directory inside the deepest parent that contains `destination`
+    fn next_path(&self, destination: &Path) -> (usize, PathBuf) {
+        let dir: usize = self
+            .dirs
+            .iter()
+            .enumerate()
+            .filter(|(_, (parent, _))| destination.starts_with(parent))
+            .max_by_key(|(_, (parent, _))| parent.components().count())
+            .map_or(0, |(index, _)| index);
+
+        (
+            dir,
+            self.dirs[dir].1.path().join(self.entries.len().to_string()),
+        )
+    }
+
+    /// Where to write the file or directory that ends up at `destination`, replacing what is there.
+    pub fn path_for(&mut self, destination: &Path) -> PathBuf {
+        let (dir, staged): (usize, PathBuf) = self.next_path(destination);
+        self.entries.push(Entry {
+            dir,
+            staged: staged.clone(),
+            destination: destination.to_path_buf(),
+            file_by_file: false,
+        });
+        staged
+    }
+
+    /// A fresh directory whose files end up in `destination`, each replacing the file of its name.
+    pub fn dir_for_files(&mut self, destination: &Path) -> Result<PathBuf> {
+        let (dir, staged): (usize, PathBuf) = self.next_path(destination);
+        fs::create_dir(&staged)?;
+        self.entries.push(Entry {
+            dir,
+            staged: staged.clone(),
+            destination: destination.to_path_buf(),
+            file_by_file: true,
+        });
+        Ok(staged)
+    }
+
+    /// Moves everything staged into place, in the order it was staged. What a move replaces is
+    /// kept aside until all moves succeeded, and put back if one of them fails.
+    pub fn commit(self) -> Result<usize> {
+        // Staged path, destination and the staging directory it came from
+        let mut moves: Vec<(PathBuf, PathBuf, usize)> = Vec::new();
+        for entry in &self.entries {
+            if !entry.staged.exists() {
+                return Err(anyhow!(
+                    "Nothing was staged for {}",
+                    entry.destination.display()
+                ));
+            }
+
+            if entry.file_by_file {
+                for file in fs::read_dir(&entry.staged)? {
+                    let file = file?;
+                    moves.push((
+                        file.path(),
+                        entry.destination.join(file.file_name()),
+                        entry.dir,
+                    ));
+                }
+            } else {
+                moves.push((entry.staged.clone(), entry.destination.clone(), entry.dir));
+            }
+        }
+
+        // What a move replaces is kept in the staging directory the move came from, so that is a rename too
+        for (_, dir) in &self.dirs {
+            fs::create_dir(dir.path().join("replaced"))?;
+        }
+
+        // Destinations already moved into place, with where the one each replaced was kept
+        let mut done: Vec<(&Path, Option<PathBuf>)> = Vec::new();
+        for (index, (staged, destination, dir)) in moves.iter().enumerate() {
+            let backup: PathBuf = self.dirs[*dir]
+                .1
+                .path()
+                .join("replaced")
+                .join(index.to_string());
+
+            let result: Result<Option<PathBuf>> = move_into_place(staged, destination, &backup);
+            match result {
+                Ok(replaced) => done.push((destination, replaced)),
+                Err(e) => {
+                    roll_back(&done);
+                    return Err(anyhow!(
+                        "Failed to move {} into place, rolled back: {}",
+                        destination.display(),
+                        e
+                    ));
+                }
+            }
+        }
+
+        Ok(moves.len())
+    }
+}
+
+// Keeps what is at `destination` as `backup`, then renames `staged` to `destination`
+fn move_into_place(staged: &Path, destination: &Path, backup: &Path) -> Result<Option<PathBuf>> {
+    if let Some(parent) = destination.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let replaced: Option<PathBuf> = if destination.exists() {
+        fs::rename(destination, backup)?;
+        Some(backup.to_path_buf())
+    } else {
+        None
+    };
+
+    if let Err(e) = fs::rename(staged, destination) {
+        if let Some(backup) = &replaced {
+            let _ = fs::rename(backup, destination);
+        }
+        return Err(e.into());
+    }
+
+    Ok(replaced)
+}
+
+// Undoes the moves that succeeded, newest first, as far as it can
+fn roll_back(done: &[(&Path, Option<PathBuf>)]) {
+    for (destination, replaced) in done.iter().rev() {
+        let removed = if destination.is_dir() {
+            fs::remove_dir_all(destination)
+        } else {
+            fs::remove_file(destination)
+        };
+        if let Err(e) = removed {
+            eprintln!("Could not remove {}: {}", destination.display(), e);
+            continue;
+        }
+
+        if let Some(backup) = replaced
+            && let Err(e) = fs::rename(backup, destination)
+        {
+            eprintln!("Could not restore {}: {}", destination.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_files_and_directories_into_place() {
+        let root: TempDir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("last.txt"), "old").unwrap();
+        fs::write(root.path().join("keep.txt"), "untouched").unwrap();
+
+        let mut staging: Staging = Staging::new(&[root.path()]).unwrap();
+        fs::write(staging.path_for(&root.path().join("last.txt")), "new").unwrap();
+
+        let version_dir: PathBuf = staging.path_for(&root.path().join("1").join("1.0.0.1"));
+        fs::create_dir(&version_dir).unwrap();
+        fs::write(version_dir.join("original.txt"), "a").unwrap();
+
+        let files: PathBuf = staging.dir_for_files(root.path()).unwrap();
+        fs::write(files.join("launcher.sh"), "run").unwrap();
+
+        assert_eq!(staging.commit().unwrap(), 3);
+
+        let read = |path: &str| fs::read_to_string(root.path().join(path)).unwrap();
+        assert_eq!(read("last.txt"), "new");
+        assert_eq!(read("keep.txt"), "untouched");
+        assert_eq!(read("1/1.0.0.1/original.txt"), "a");
+        assert_eq!(read("launcher.sh"), "run");
+
+        // Only the staged outputs are left, the staging directory is gone
+        let leftovers: usize = fs::read_dir(root.path())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(STAGING_PREFIX)
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn rolls_back_when_a_move_fails() {
+        let root: TempDir = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("last.txt"), "old").unwrap();
+        // A file where a directory has to go makes the second move fail
+        fs::write(root.path().join("blocker"), "").unwrap();
+
+        let mut staging: Staging = Staging::new(&[root.path()]).unwrap();
+        fs::write(staging.path_for(&root.path().join("last.txt")), "new").unwrap();
+        fs::write(staging.path_for(&root.path().join("added.txt")), "b").unwrap();
+        fs::write(
+            staging.path_for(&root.path().join("blocker").join("file.txt")),
+            "x",
+        )
+        .unwrap();
+
+        assert!(staging.commit().is_err());
+
+        assert_eq!(
+            fs::read_to_string(root.path().join("last.txt")).unwrap(),
+            "old"
+        );
+        assert!(!root.path().join("added.txt").exists());
+    }
+
+    #[test]
+    fn refuses_to_commit_what_was_never_written() {
+        let root: TempDir = tempfile::tempdir().unwrap();
+
+        let mut staging: Staging = Staging::new(&[root.path()]).unwrap();
+        staging.path_for(&root.path().join("README.md"));
+
+        assert!(staging.commit().is_err());
+        assert!(!root.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn stages_each_output_under_the_deepest_parent_containing_it() {
+        let root: TempDir = tempfile::tempdir().unwrap();
+        let archive: PathBuf = root.path().join("Edge Canary");
+
+        let mut staging: Staging = Staging::new(&[root.path(), &archive]).unwrap();
+        let staged_version: PathBuf = staging.path_for(&archive.join("1").join("1.0.0.1"));
+        let staged_readme: PathBuf = staging.path_for(&root.path().join("README.md"));
+
+        assert_eq!(staged_version.parent().unwrap().parent().unwrap(), archive);
+        assert_eq!(
+            staged_readme.parent().unwrap().parent().unwrap(),
+            root.path()
+        );
+    }
+}
@@ -0,0 +1,195 @@
+/// Minimum run length used by Sysinternals strings64.exe, which this scanner replaces.
+pub const DEFAULT_MIN_LENGTH: usize = 3;
+
+/// A printable run found in a binary, with the file offset of its first byte.
+#[derive(Clone, Debug)]
+pub struct FoundString {
+    pub offset: usize,
+    pub text: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScanOptions {
+    pub min_length: usize,
+    pub ascii: bool,
+    pub utf16: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH,
+            ascii: true,
+            utf16: true,
+        }
+    }
+}
+
+// Same character set strings64.exe treats as printable
+fn is_printable(byte: u8) -> bool {
+    byte.is_ascii_graphic() || byte == b' ' || byte == b'\t'
+}
+
+/// Walks `data` and returns every ASCII and UTF-16LE run of printable characters
+/// that is at least `options.min_length` characters long, ordered by offset.
+pub fn scan(data: &[u8], options: &ScanOptions) -> Vec<FoundString> {
+    let min_length: usize = options.min_length.max(1);
+    let mut found: Vec<FoundString> = Vec::new();
+
+    if options.ascii {
+        scan_ascii(data, min_length, &mut found);
+    }
+
+    if options.utf16 {
+        // UTF-16 strings are not guaranteed to be 2-byte aligned in the file, so walk both phases
+        scan_utf16le(data, 0, min_length, &mut found);
+        scan_utf16le(data, 1, min_length, &mut found);
+    }
+
+    found.sort_by_key(|s| s.offset);
+    found
+}
+
+fn scan_ascii(data: &[u8], min_length: usize, found: &mut Vec<FoundString>) {
+    let mut start: usize = 0;
+
+    for (index, &byte) in data.iter().enumerate() {
+        if is_printable(byte) {
+            continue;
+        }
+        push_ascii_run(data, start, index, min_length, found);
+        start = index + 1;
+    }
+
+    push_ascii_run(data, start, data.len(), min_length, found);
+}
+
+fn push_ascii_run(
+    data: &[u8],
+    start: usize,
+    end: usize,
+    min_length: usize,
+    found: &mut Vec<FoundString>,
+) {
+    if end - start < min_length {
+        return;
+    }
+
+    // Every byte in the run is printable ASCII so this cannot lose information
+    let text: String = String::from_utf8_lossy(&data[start..end]).into_owned();
+    found.push(FoundString {
+        offset: start,
+        text,
+    });
+}
+
+fn scan_utf16le(data: &[u8], phase: usize, min_length: usize, found: &mut Vec<FoundString>) {
+    if data.len() <= phase {
+        return;
+    }
+
+    let mut run: String = String::new();
+    let mut run_start: usize = phase;
+
+    for (index, pair) in data[phase..].chunks_exact(2).enumerate() {
+        let offset: usize = phase + index * 2;
+
+        if pair[1] == 0 && is_printable(pair[0]) {
+            if run.is_empty() {
+                run_start = offset;
+            }
+            run.push(pair[0] as char);
+            continue;
+        }
+
+        if run.len() >= min_length {
+            found.push(FoundString {
+                offset: run_start,
+                text: std::mem::take(&mut run),
+            });
+        } else {
+            run.clear();
+        }
+    }
+
+    if run.len() >= min_length {
+        found.push(FoundString {
+            offset: run_start,
+            text: run,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.bytes().flat_map(|byte| [byte, 0]).collect()
+    }
+
+    fn runs(data: &[u8], options: &ScanOptions) -> Vec<(usize, String)> {
+        scan(data, options)
+            .into_iter()
+            .map(|found| (found.offset, found.text))
+            .collect()
+    }
+
+    #[test]
+    fn finds_ascii_runs_down_to_min_length_and_at_the_end() {
+        let data: &[u8] = b"\x00hello\x01ab\x02world";
+        let options: ScanOptions = ScanOptions {
+            utf16: false,
+            ..ScanOptions::default()
+        };
+
+        assert_eq!(
+            runs(data, &options),
+            [(1, "hello".to_string()), (10, "world".to_string())]
+        );
+
+        let shorter: ScanOptions = ScanOptions {
+            min_length: 2,
+            ..options
+        };
+        assert_eq!(
+            runs(data, &shorter),
+            [
+                (1, "hello".to_string()),
+                (7, "ab".to_string()),
+                (10, "world".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_utf16le_runs_at_both_byte_phases() {
+        let mut data: Vec<u8> = utf16le("abc");
+        data.push(0xFF);
+        data.extend(utf16le("xyz"));
+
+        let options: ScanOptions = ScanOptions {
+            ascii: false,
+            ..ScanOptions::default()
+        };
+
+        assert_eq!(
+            runs(&data, &options),
+            [(0, "abc".to_string()), (7, "xyz".to_string())]
+        );
+    }
+
+    #[test]
+    fn drops_utf16le_runs_below_min_length() {
+        let mut data: Vec<u8> = utf16le("ab");
+        data.extend([0xFF, 0xFF]);
+        data.extend(utf16le("long"));
+
+        let options: ScanOptions = ScanOptions {
+            ascii: false,
+            ..ScanOptions::default()
+        };
+
+        assert_eq!(runs(&data, &options), [(6, "long".to_string())]);
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{Result, anyhow};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Folder holding one sub-folder per topic, and the topic definitions.
+pub const TOPICS_DIR: &str = "Feature Specific shortcuts";
+pub const TOPICS_FILE: &str = "topics.json";
+
+/// A group of features about one product area, such as Copilot, with its own folder.
+#[derive(Deserialize, Debug)]
+pub struct Topic {
+    /// Name of the topic's folder
+    pub name: String,
+    /// Name used in the generated text, the folder name when omitted
+    #[serde(default)]
+    pub title: Option<String>,
+    /// A feature belongs to the topic when any of these regular expressions matches it
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// ... or when it contains any of these, ignoring case
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl Topic {
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The features that belong to the topic, in the order given.
+    pub fn select<'a>(
+        &self,
+        features: impl IntoIterator<Item = &'a String>,
+    ) -> Result<Vec<String>> {
+        let regexes: Vec<Regex> = self
+            .patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<Result<Vec<Regex>, regex::Error>>()
+            .map_err(|e| anyhow!("Invalid pattern in topic {}: {}", self.name, e))?;
+        let keywords: Vec<String> = self
+            .keywords
+            .iter()
+            .map(|keyword| keyword.to_lowercase())
+            .collect();
+
+        Ok(features
+            .into_iter()
+            .filter(|feature| {
+                let lowercase: String = feature.to_lowercase();
+                regexes.iter().any(|regex| regex.is_match(feature))
+                    || keywords.iter().any(|keyword| lowercase.contains(keyword))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+/// Reads the topic definitions, no topics when the file does not exist.
+pub fn load(dir: &Path) -> Result<Vec<Topic>> {
+    let file_path: PathBuf = dir.join(TOPICS_FILE);
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content: String = fs::read_to_string(&file_path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse {}: {}", file_path.display(), e))
+}
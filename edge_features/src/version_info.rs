@@ -0,0 +1,294 @@
+use crate::pe::{self, PeFile, RT_VERSION};
+use anyhow::{Result, anyhow};
+
+const VS_FFI_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// The product version as recorded in the two halves of a VS_VERSIONINFO resource.
+#[derive(Debug, Default)]
+pub struct VersionInfo {
+    /// From the binary VS_FIXEDFILEINFO block
+    pub fixed_product_version: Option<String>,
+    /// From the "ProductVersion" entry of the first StringFileInfo table
+    pub string_product_version: Option<String>,
+}
+
+impl VersionInfo {
+    /// The product version, preferring the fixed block over the free-form string.
+    pub fn product_version(&self) -> Option<&str> {
+        self.fixed_product_version
+            .as_deref()
+            .or(self.string_product_version.as_deref())
+    }
+
+    /// Fails unless every product version the resource records equals `expected`.
+    pub fn verify(&self, expected: &str) -> Result<()> {
+        let recorded = [
+            ("VS_FIXEDFILEINFO", &self.fixed_product_version),
+            ("StringFileInfo", &self.string_product_version),
+        ];
+
+        for (source, version) in recorded {
+            if let Some(version) = version
+                && version != expected
+            {
+                return Err(anyhow!(
+                    "Version mismatch: expected {} but the {} product version of msedge.dll is {}",
+                    expected,
+                    source,
+                    version
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the image's VS_VERSIONINFO resource, or None if it has none.
+pub fn read(data: &[u8], pe: &PeFile) -> Result<Option<VersionInfo>> {
+    let Some(resource) = pe.find_resource(data, RT_VERSION)? else {
+        return Ok(None);
+    };
+
+    let root: Block = Block::parse(resource, 0)?;
+    if root.key != "VS_VERSION_INFO" {
+        return Err(anyhow!(
+            "Unexpected version resource key '{}', expected VS_VERSION_INFO",
+            root.key
+        ));
+    }
+
+    let mut info: VersionInfo = VersionInfo::default();
+
+    if root.value_length >= 52 {
+        let fixed: usize = root.value_offset;
+        if pe::read_u32(resource, fixed)? != VS_FFI_SIGNATURE {
+            return Err(anyhow!(
+                "VS_FIXEDFILEINFO signature not found in the version resource"
+            ));
+        }
+
+        let product_ms: u32 = pe::read_u32(resource, fixed + 16)?;
+        let product_ls: u32 = pe::read_u32(resource, fixed + 20)?;
+
+        info.fixed_product_version = Some(format!(
+            "{}.{}.{}.{}",
+            product_ms >> 16,
+            product_ms & 0xFFFF,
+            product_ls >> 16,
+            product_ls & 0xFFFF
+        ));
+    }
+
+    // VS_VERSIONINFO -> StringFileInfo -> StringTable (one per language) -> String
+    for string_file_info in root.children(resource)? {
+        if string_file_info.key != "StringFileInfo" {
+            continue;
+        }
+
+        let Some(table) = string_file_info.children(resource)?.into_iter().next() else {
+            continue;
+        };
+
+        for entry in table.children(resource)? {
+            if entry.key == "ProductVersion" {
+                info.string_product_version = Some(entry.text(resource)?.trim().to_string());
+            }
+        }
+    }
+
+    Ok(Some(info))
+}
+
+// The header shared by every node of a version resource
+struct Block {
+    start: usize,
+    length: usize,
+    value_length: usize,
+    is_text: bool,
+    key: String,
+    value_offset: usize,
+}
+
+impl Block {
+    fn parse(data: &[u8], start: usize) -> Result<Self> {
+        let length: usize = pe::read_u16(data, start)? as usize;
+        let value_length: usize = pe::read_u16(data, start + 2)? as usize;
+        let is_text: bool = pe::read_u16(data, start + 4)? == 1;
+
+        if length < 6 {
+            return Err(anyhow!("Malformed version resource block at {:#x}", start));
+        }
+
+        // wLength, wValueLength and wType, then the key padded to a 4-byte boundary
+        let (key, key_end): (String, usize) = read_utf16_string(data, start + 6)?;
+
+        Ok(Self {
+            start,
+            length,
+            value_length,
+            is_text,
+            key,
+            value_offset: key_end.next_multiple_of(4),
+        })
+    }
+
+    // Text values are measured in UTF-16 code units, binary ones in bytes
+    fn value_size(&self) -> usize {
+        if self.is_text {
+            self.value_length * 2
+        } else {
+            self.value_length
+        }
+    }
+
+    fn text(&self, data: &[u8]) -> Result<String> {
+        if self.value_length == 0 {
+            return Ok(String::new());
+        }
+        read_utf16_string(data, self.value_offset).map(|(text, _)| text)
+    }
+
+    fn children(&self, data: &[u8]) -> Result<Vec<Block>> {
+        let end: usize = self.start + self.length;
+        let mut offset: usize = (self.value_offset + self.value_size()).next_multiple_of(4);
+        let mut children: Vec<Block> = Vec::new();
+
+        while offset + 6 <= end {
+            let child: Block = Block::parse(data, offset)?;
+            offset = (offset + child.length).next_multiple_of(4);
+            children.push(child);
+        }
+
+        Ok(children)
+    }
+}
+
+// Reads a NUL-terminated UTF-16LE string and returns it with the offset just past the terminator
+fn read_utf16_string(data: &[u8], offset: usize) -> Result<(String, usize)> {
+    let mut units: Vec<u16> = Vec::new();
+    let mut position: usize = offset;
+
+    loop {
+        let unit: u16 = pe::read_u16(data, position)?;
+        position += 2;
+        if unit == 0 {
+            break;
+        }
+        units.push(unit);
+    }
+
+    Ok((String::from_utf16_lossy(&units), position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::tests::{RSRC_OFFSET, RSRC_RVA, put_u16, put_u32, synthetic_pe};
+
+    fn pad(data: &mut Vec<u8>) {
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    // One node of a version resource: header, key, value and children, each 4-byte aligned
+    fn block(
+        key: &str,
+        value_length: u16,
+        is_text: bool,
+        value: &[u8],
+        children: &[u8],
+    ) -> Vec<u8> {
+        let mut data: Vec<u8> = vec![0; 6];
+        data.extend(key.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        pad(&mut data);
+        data.extend(value);
+        pad(&mut data);
+        data.extend(children);
+
+        let length: u16 = data.len() as u16;
+        put_u16(&mut data, 0, length);
+        put_u16(&mut data, 2, value_length);
+        put_u16(&mut data, 4, u16::from(is_text));
+        data
+    }
+
+    // VS_VERSIONINFO with the fixed block's product version and a ProductVersion string
+    fn version_resource(fixed: [u16; 4], string: &str) -> Vec<u8> {
+        let mut fixed_info: Vec<u8> = vec![0; 52];
+        put_u32(&mut fixed_info, 0, VS_FFI_SIGNATURE);
+        put_u32(
+            &mut fixed_info,
+            16,
+            (u32::from(fixed[0]) << 16) | u32::from(fixed[1]),
+        );
+        put_u32(
+            &mut fixed_info,
+            20,
+            (u32::from(fixed[2]) << 16) | u32::from(fixed[3]),
+        );
+
+        let text: Vec<u8> = string
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let product_version: Vec<u8> =
+            block("ProductVersion", (text.len() / 2) as u16, true, &text, &[]);
+        let table: Vec<u8> = block("040904B0", 0, true, &[], &product_version);
+        let string_file_info: Vec<u8> = block("StringFileInfo", 0, true, &[], &table);
+
+        block("VS_VERSION_INFO", 52, false, &fixed_info, &string_file_info)
+    }
+
+    // The synthetic image with `resource` as its only RT_VERSION resource
+    fn image_with(resource: &[u8]) -> Vec<u8> {
+        let mut data: Vec<u8> = synthetic_pe();
+        let root: usize = RSRC_OFFSET;
+
+        // Type, name and language directories with one entry each, then the data entry
+        for (directory, id, target) in [
+            (0, RT_VERSION, 0x8000_0018),
+            (0x18, 1, 0x8000_0030),
+            (0x30, 0x409, 0x48),
+        ] {
+            put_u16(&mut data, root + directory + 14, 1);
+            put_u32(&mut data, root + directory + 16, id);
+            put_u32(&mut data, root + directory + 20, target);
+        }
+        put_u32(&mut data, root + 0x48, RSRC_RVA + 0x60);
+        put_u32(&mut data, root + 0x4C, resource.len() as u32);
+        data[root + 0x60..root + 0x60 + resource.len()].copy_from_slice(resource);
+
+        data
+    }
+
+    #[test]
+    fn reads_both_product_versions() {
+        let data: Vec<u8> = image_with(&version_resource([150, 0, 3000, 1], "150.0.3000.1"));
+        let pe: PeFile = PeFile::parse(&data).unwrap();
+
+        let info: VersionInfo = read(&data, &pe).unwrap().unwrap();
+        assert_eq!(info.fixed_product_version.as_deref(), Some("150.0.3000.1"));
+        assert_eq!(info.string_product_version.as_deref(), Some("150.0.3000.1"));
+        assert!(info.verify("150.0.3000.1").is_ok());
+        assert!(info.verify("150.0.3000.2").is_err());
+    }
+
+    #[test]
+    fn fails_verification_when_the_halves_disagree() {
+        let data: Vec<u8> = image_with(&version_resource([150, 0, 3000, 1], "150.0.3000.2"));
+        let pe: PeFile = PeFile::parse(&data).unwrap();
+
+        let info: VersionInfo = read(&data, &pe).unwrap().unwrap();
+        assert_eq!(info.product_version(), Some("150.0.3000.1"));
+        assert!(info.verify("150.0.3000.1").is_err());
+    }
+
+    #[test]
+    fn returns_none_without_a_version_resource() {
+        let data: Vec<u8> = synthetic_pe();
+        let pe: PeFile = PeFile::parse(&data).unwrap();
+
+        assert!(read(&data, &pe).unwrap().is_none());
+    }
+}